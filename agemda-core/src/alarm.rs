@@ -0,0 +1,28 @@
+use chrono::{DateTime, Local, TimeDelta};
+
+/// The date of todo an alarm is relative to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Related {
+    Start,
+    /// The due, the end of todo.
+    Due,
+}
+
+/// When an alarm is triggered, corresponding to VALARM TRIGGER.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Trigger {
+    /// An offset from the start or due.
+    Relative {
+        related: Related,
+        offset: TimeDelta,
+    },
+    Absolute(DateTime<Local>),
+}
+
+/// An alarm of todo.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Alarm {
+    pub trigger: Trigger,
+    /// The time of alarm, `None` if relative to a missing date.
+    pub time: Option<DateTime<Local>>,
+}
@@ -0,0 +1,20 @@
+use std::ops::Range;
+
+use chrono::{DateTime, Local, TimeDelta};
+
+/// A clocked period of work on todo, from a `CLOCK:` line under the task.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Clock {
+    pub start: DateTime<Local>,
+    /// `None` while the clock is running.
+    pub end: Option<DateTime<Local>>,
+    /// The byte range of the period in file, e.g. `2025-03-09T10:00--11:30`.
+    pub range: Range<usize>,
+}
+
+impl Clock {
+    /// The clocked time, up to now if running.
+    pub fn duration(&self, now: DateTime<Local>) -> TimeDelta {
+        self.end.unwrap_or(now) - self.start
+    }
+}
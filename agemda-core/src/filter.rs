@@ -0,0 +1,69 @@
+use crate::{Status, Todo};
+
+/// Filter of todos, shared by the calendar and commands.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Filter {
+    /// Text to be contained in the heading path, case insensitive.
+    pub context: Option<String>,
+    /// Only todos of this status, regardless of `should_show_completed`.
+    pub status: Option<Status>,
+    /// Only todos with this tag, case insensitive.
+    pub tag: Option<String>,
+    /// Only todos assigned to this name, case insensitive.
+    pub assignee: Option<String>,
+    /// Only own todos of this name, assigned to it or to no one, case insensitive.
+    pub me: Option<String>,
+    /// Include completed and cancelled todos.
+    pub should_show_completed: bool,
+    /// Only todos whose link has this extension key, and this value if given.
+    pub extension: Option<(String, Option<String>)>,
+}
+
+impl Filter {
+    pub fn matches(&self, todo: &Todo) -> bool {
+        let status = todo.attributes.as_ref().ok().map(|agmd| agmd.status);
+        match self.status {
+            Some(expected) if status != Some(expected) => return false,
+            Some(_) => {}
+            None if !self.should_show_completed && status.is_some_and(Status::is_closed) => {
+                return false;
+            }
+            None => {}
+        }
+        if let Some(context) = &self.context {
+            let context = context.to_lowercase();
+            if !todo.metadata.context().to_lowercase().contains(&context) {
+                return false;
+            }
+        }
+        if let Some(tag) = &self.tag
+            && !todo.has_tag(tag)
+        {
+            return false;
+        }
+        if let Some(assignee) = &self.assignee
+            && !todo.is_assigned_to(assignee)
+        {
+            return false;
+        }
+        if let Some(me) = &self.me
+            && !todo.assignees.is_empty()
+            && !todo.is_assigned_to(me)
+        {
+            return false;
+        }
+        if let Some((key, expected)) = &self.extension {
+            let value = todo
+                .attributes
+                .as_ref()
+                .ok()
+                .and_then(|agmd| agmd.extension(key));
+            match (value, expected) {
+                (None, _) => return false,
+                (Some(value), Some(expected)) if value != expected => return false,
+                _ => {}
+            }
+        }
+        true
+    }
+}
@@ -0,0 +1,112 @@
+use std::collections::HashMap;
+
+use crate::Todo;
+
+/// Resolve the uids in `depends` of todos into `prerequisites`.
+///
+/// Uids of no todo are left unresolved, see [`missing_dependencies`].
+pub fn resolve_dependencies(todos: &mut [Todo]) {
+    let mut by_uid: HashMap<String, Vec<usize>> = HashMap::new();
+    for (index, todo) in todos.iter().enumerate() {
+        if let Some(uid) = &todo.uid {
+            by_uid.entry(uid.clone()).or_default().push(index);
+        }
+    }
+    for todo in todos.iter_mut() {
+        todo.prerequisites = todo
+            .depends
+            .iter()
+            .filter_map(|uid| by_uid.get(uid))
+            .flatten()
+            .copied()
+            .collect();
+    }
+}
+
+/// Pairs of todo index and the uid in its `depends` matching no todo.
+pub fn missing_dependencies(todos: &[Todo]) -> Vec<(usize, String)> {
+    let mut missing = vec![];
+    for (index, todo) in todos.iter().enumerate() {
+        for uid in &todo.depends {
+            let is_resolved = todo
+                .prerequisites
+                .iter()
+                .any(|&i| todos[i].uid.as_ref() == Some(uid));
+            if !is_resolved {
+                missing.push((index, uid.clone()));
+            }
+        }
+    }
+    missing
+}
+
+/// Cycles of dependencies, each as the indices of todos in it.
+///
+/// These are the strongly connected components of more than one todo,
+/// or of a todo depending on itself.
+pub fn dependency_cycles(todos: &[Todo]) -> Vec<Vec<usize>> {
+    let mut tarjan = Tarjan {
+        todos,
+        next: 0,
+        indices: vec![None; todos.len()],
+        low_links: vec![0; todos.len()],
+        stack: vec![],
+        on_stack: vec![false; todos.len()],
+        cycles: vec![],
+    };
+    for index in 0..todos.len() {
+        if tarjan.indices[index].is_none() {
+            tarjan.visit(index);
+        }
+    }
+    tarjan.cycles
+}
+
+/// State of Tarjan's strongly connected components algorithm.
+struct Tarjan<'a> {
+    todos: &'a [Todo],
+    next: usize,
+    indices: Vec<Option<usize>>,
+    low_links: Vec<usize>,
+    stack: Vec<usize>,
+    on_stack: Vec<bool>,
+    cycles: Vec<Vec<usize>>,
+}
+
+impl Tarjan<'_> {
+    fn visit(&mut self, v: usize) {
+        self.indices[v] = Some(self.next);
+        self.low_links[v] = self.next;
+        self.next += 1;
+        self.stack.push(v);
+        self.on_stack[v] = true;
+
+        for &w in &self.todos[v].prerequisites {
+            match self.indices[w] {
+                None => {
+                    self.visit(w);
+                    self.low_links[v] = self.low_links[v].min(self.low_links[w]);
+                }
+                Some(index) if self.on_stack[w] => {
+                    self.low_links[v] = self.low_links[v].min(index);
+                }
+                Some(_) => {}
+            }
+        }
+
+        if Some(self.low_links[v]) == self.indices[v] {
+            let mut component = vec![];
+            while let Some(w) = self.stack.pop() {
+                self.on_stack[w] = false;
+                component.push(w);
+                if w == v {
+                    break;
+                }
+            }
+            if component.len() > 1 || self.todos[v].prerequisites.contains(&v) {
+                component.sort();
+                self.cycles.push(component);
+            }
+        }
+    }
+}
@@ -1,28 +1,205 @@
-use std::path::PathBuf;
+use std::{collections::BTreeMap, ops::Range, path::PathBuf};
 
-use chrono::{DateTime, Local};
+use chrono::{DateTime, Local, TimeDelta};
+
+mod alarm;
+mod clock;
+mod filter;
+pub mod graph;
+mod status;
+mod summary;
+
+pub use alarm::{Alarm, Related, Trigger};
+pub use clock::Clock;
+pub use filter::Filter;
+pub use status::Status;
+pub use summary::{Span, SpanStyle, Summary};
 
 /// The attributes specified in `<agmd:>` link.
+#[derive(Debug, Clone, PartialEq)]
 pub struct Attributes {
+    pub status: Status,
     pub start: Option<DateTime<Local>>,
     pub due: Option<DateTime<Local>>,
     pub completed: Option<DateTime<Local>>,
+    /// Priority in VTODO scale, 1 for the highest and 9 for the lowest.
+    pub priority: Option<u8>,
+    pub alarms: Vec<Alarm>,
+    /// The estimated time of work.
+    pub estimate: Option<TimeDelta>,
+    /// The time of work recorded by `spent=`, besides clocks.
+    pub spent: Option<TimeDelta>,
+    /// Pairs of keys unknown to agmd and their values, in order, e.g. `ticket=OPS-123`.
+    pub extensions: Vec<(String, String)>,
 }
 
 impl Attributes {
     pub fn is_completed(&self) -> bool {
         self.completed.is_some()
     }
+
+    /// Whether priority is 1 to 4, the high ones of VTODO.
+    pub fn is_high_priority(&self) -> bool {
+        self.priority.is_some_and(|priority| priority <= 4)
+    }
+
+    /// Whether completed or cancelled.
+    pub fn is_closed(&self) -> bool {
+        self.status.is_closed()
+    }
+
+    /// The value of the extension key, e.g. `OPS-123` of `ticket`.
+    pub fn extension(&self, key: &str) -> Option<&str> {
+        self.extensions
+            .iter()
+            .find(|(k, _)| k == key)
+            .map(|(_, value)| value.as_str())
+    }
 }
 
+#[derive(Debug, Clone, PartialEq)]
 pub struct Metadata {
     pub path: PathBuf,
+    /// The fields of the front matter of the file, if any.
+    pub front_matter: BTreeMap<String, String>,
+    /// The text of enclosing headings, from outermost to innermost.
+    pub headings: Vec<String>,
+    /// The line of the task in file, from 1.
+    pub line: usize,
+    /// The byte range of the task marker in file, e.g. `[ ]`.
+    pub marker: Range<usize>,
+    /// The byte range of the own link of the task in file, e.g. `<agmd:2025-03-09>`.
+    pub link: Option<Range<usize>>,
+    /// The byte offset of the end of summary in file.
+    pub summary_end: usize,
+    /// The byte offset of the end of the own content of the task in file,
+    /// its summary, description and clocks.
+    pub content_end: usize,
+}
+
+impl Metadata {
+    /// The heading path of the todo, e.g. `Clients > Acme > Q2`.
+    pub fn context(&self) -> String {
+        self.headings.join(" > ")
+    }
+}
+
+/// The description of todo, from the content nested in the task.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Description {
+    /// The markdown source, dedented.
+    pub markdown: String,
+    /// The text without markup.
+    pub plain: String,
 }
 
 /// A todo task corresponding to ical VTODO.
+#[derive(Debug, Clone, PartialEq)]
 pub struct Todo {
-    pub summary: String,
+    pub summary: Summary,
+    /// Nested content of the task other than subtasks.
+    pub description: Option<Description>,
+    /// The stable identifier from `uid=` of the own link.
+    pub uid: Option<String>,
+    /// Uids of the todos this todo depends on, from `depends=`.
+    pub depends: Vec<String>,
+    /// Indices of the todos of `depends` in the loaded todos.
+    pub prerequisites: Vec<usize>,
+    /// Clocked periods of work, from `CLOCK:` lines under the task.
+    pub clocks: Vec<Clock>,
+    /// Tags from the link and `#tag` in summary, without `#`.
+    pub tags: Vec<String>,
+    /// Names of assignees from the link and `@name` in summary, without `@`.
+    pub assignees: Vec<String>,
     /// When parse error, return the raw string.
     pub attributes: Result<Attributes, String>,
     pub metadata: Metadata,
+    /// Index of the parent todo in the loaded todos.
+    pub parent: Option<usize>,
+    /// Number of ancestor todos.
+    pub depth: usize,
+    /// Indices of the child todos in the loaded todos.
+    pub children: Vec<usize>,
+}
+
+impl Todo {
+    /// Whether the todo has the tag, case insensitive.
+    pub fn has_tag(&self, tag: &str) -> bool {
+        let tag = tag.to_lowercase();
+        self.tags.iter().any(|t| t.to_lowercase() == tag)
+    }
+
+    /// Indices of the prerequisites not completed or cancelled yet.
+    pub fn blockers(&self, todos: &[Todo]) -> Vec<usize> {
+        self.prerequisites
+            .iter()
+            .copied()
+            .filter(|&i| {
+                !todos[i]
+                    .attributes
+                    .as_ref()
+                    .is_ok_and(|agmd| agmd.is_closed())
+            })
+            .collect()
+    }
+
+    /// Whether any prerequisite is not completed or cancelled yet.
+    pub fn is_blocked(&self, todos: &[Todo]) -> bool {
+        !self.blockers(todos).is_empty()
+    }
+
+    /// The total time of work, of `spent=` and clocks up to now.
+    pub fn time_spent(&self, now: DateTime<Local>) -> TimeDelta {
+        let spent = self
+            .attributes
+            .as_ref()
+            .ok()
+            .and_then(|agmd| agmd.spent)
+            .unwrap_or_default();
+        spent
+            + self
+                .clocks
+                .iter()
+                .map(|clock| clock.duration(now))
+                .sum::<TimeDelta>()
+    }
+
+    /// The running clock, if any.
+    pub fn running_clock(&self) -> Option<&Clock> {
+        self.clocks.iter().find(|clock| clock.end.is_none())
+    }
+
+    /// Whether the todo is assigned to the name, case insensitive.
+    pub fn is_assigned_to(&self, name: &str) -> bool {
+        let name = name.to_lowercase();
+        self.assignees.iter().any(|n| n.to_lowercase() == name)
+    }
+
+    /// The progress of children, `None` if there is no child.
+    ///
+    /// Cancelled children are not counted.
+    pub fn progress(&self, todos: &[Todo]) -> Option<Progress> {
+        if self.children.is_empty() {
+            return None;
+        }
+        let status = |i: usize| todos[i].attributes.as_ref().ok().map(|agmd| agmd.status);
+        let done = self
+            .children
+            .iter()
+            .filter(|&&i| status(i) == Some(Status::Completed))
+            .count();
+        let total = self
+            .children
+            .iter()
+            .filter(|&&i| status(i) != Some(Status::Cancelled))
+            .count();
+        Some(Progress { done, total })
+    }
+}
+
+/// Number of completed children out of all children.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Progress {
+    pub done: usize,
+    pub total: usize,
 }
@@ -0,0 +1,104 @@
+use std::{fmt, str::FromStr};
+
+/// The state of todo, from the marker of task list item.
+///
+/// | marker | status        |
+/// | ------ | ------------- |
+/// | `[ ]`  | `NeedsAction` |
+/// | `[/]`  | `InProcess`   |
+/// | `[x]`  | `Completed`   |
+/// | `[-]`  | `Cancelled`   |
+/// | `[>]`  | `Deferred`    |
+/// | `[?]`  | `Question`    |
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum Status {
+    #[default]
+    NeedsAction,
+    InProcess,
+    Completed,
+    Cancelled,
+    Deferred,
+    Question,
+}
+
+impl Status {
+    pub const ALL: [Status; 6] = [
+        Status::NeedsAction,
+        Status::InProcess,
+        Status::Completed,
+        Status::Cancelled,
+        Status::Deferred,
+        Status::Question,
+    ];
+
+    /// The status of the char between brackets of task list marker.
+    pub fn from_marker(marker: char) -> Option<Self> {
+        match marker {
+            ' ' => Some(Status::NeedsAction),
+            '/' => Some(Status::InProcess),
+            'x' | 'X' => Some(Status::Completed),
+            '-' => Some(Status::Cancelled),
+            '>' => Some(Status::Deferred),
+            '?' => Some(Status::Question),
+            _ => None,
+        }
+    }
+
+    pub fn marker(self) -> char {
+        match self {
+            Status::NeedsAction => ' ',
+            Status::InProcess => '/',
+            Status::Completed => 'x',
+            Status::Cancelled => '-',
+            Status::Deferred => '>',
+            Status::Question => '?',
+        }
+    }
+
+    /// The name in kebab case, e.g. `in-process`.
+    pub fn name(self) -> &'static str {
+        match self {
+            Status::NeedsAction => "needs-action",
+            Status::InProcess => "in-process",
+            Status::Completed => "completed",
+            Status::Cancelled => "cancelled",
+            Status::Deferred => "deferred",
+            Status::Question => "question",
+        }
+    }
+
+    /// The value of VTODO STATUS, which has no deferred or question.
+    pub fn vtodo(self) -> &'static str {
+        match self {
+            Status::NeedsAction | Status::Deferred | Status::Question => "NEEDS-ACTION",
+            Status::InProcess => "IN-PROCESS",
+            Status::Completed => "COMPLETED",
+            Status::Cancelled => "CANCELLED",
+        }
+    }
+
+    /// Whether nothing is left to do, i.e. completed or cancelled.
+    pub fn is_closed(self) -> bool {
+        matches!(self, Status::Completed | Status::Cancelled)
+    }
+}
+
+impl fmt::Display for Status {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl FromStr for Status {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Status::ALL
+            .into_iter()
+            .find(|status| status.name() == s)
+            .ok_or_else(|| {
+                let names: Vec<_> = Status::ALL.iter().map(|status| status.name()).collect();
+                format!("unknown status {s}, expect one of {}", names.join(", "))
+            })
+    }
+}
@@ -0,0 +1,81 @@
+use std::fmt;
+
+/// The inline markdown styles of a span.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SpanStyle {
+    pub code: bool,
+    pub emphasis: bool,
+    pub strong: bool,
+    pub strikethrough: bool,
+    /// Visible text of a link.
+    pub link: bool,
+}
+
+/// A run of text with the same style.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Span {
+    pub text: String,
+    pub style: SpanStyle,
+}
+
+/// The summary of todo, keeping inline markdown as styled spans.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Summary {
+    pub spans: Vec<Span>,
+}
+
+impl Summary {
+    /// Append text, merged into the last span if of the same style.
+    pub fn push(&mut self, text: &str, style: SpanStyle) {
+        if text.is_empty() {
+            return;
+        }
+        match self.spans.last_mut() {
+            Some(last) if last.style == style => last.text.push_str(text),
+            _ => self.spans.push(Span {
+                text: text.to_string(),
+                style,
+            }),
+        }
+    }
+
+    /// Trim whitespace at both ends, removing spans left empty.
+    pub fn trim(&mut self) {
+        while let Some(first) = self.spans.first_mut() {
+            first.text = first.text.trim_start().to_string();
+            if !first.text.is_empty() {
+                break;
+            }
+            self.spans.remove(0);
+        }
+        while let Some(last) = self.spans.last_mut() {
+            last.text.truncate(last.text.trim_end().len());
+            if !last.text.is_empty() {
+                break;
+            }
+            self.spans.pop();
+        }
+    }
+
+    /// The text without styles.
+    pub fn plain(&self) -> String {
+        self.spans.iter().map(|span| span.text.as_str()).collect()
+    }
+}
+
+impl fmt::Display for Summary {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for span in &self.spans {
+            f.write_str(&span.text)?;
+        }
+        Ok(())
+    }
+}
+
+impl From<&str> for Summary {
+    fn from(text: &str) -> Self {
+        let mut summary = Summary::default();
+        summary.push(text, SpanStyle::default());
+        summary
+    }
+}
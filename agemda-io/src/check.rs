@@ -0,0 +1,190 @@
+use std::collections::BTreeMap;
+
+use agemda_core::{
+    Todo,
+    graph::{dependency_cycles, missing_dependencies},
+};
+
+/// A problem of loaded todos.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Problem {
+    /// The link of todo is malformed.
+    Malformed { index: usize },
+    /// Todos sharing the same uid.
+    DuplicateUid { uid: String, indices: Vec<usize> },
+    /// The uid in `depends` matches no todo.
+    MissingDependency { index: usize, uid: String },
+    /// Todos depending on each other in a cycle.
+    DependencyCycle { indices: Vec<usize> },
+    /// The todo is due earlier than its prerequisite.
+    DueBeforePrerequisite { index: usize, prerequisite: usize },
+}
+
+impl Problem {
+    /// The message of problem, locating todos by `path:line`.
+    pub fn message(&self, todos: &[Todo]) -> String {
+        match self {
+            Problem::Malformed { index } => {
+                format!("{}: malformed link", location(&todos[*index]))
+            }
+            Problem::DuplicateUid { uid, indices } => {
+                let locations: Vec<_> = indices.iter().map(|&i| location(&todos[i])).collect();
+                format!("duplicate uid {uid} at {}", locations.join(", "))
+            }
+            Problem::MissingDependency { index, uid } => {
+                format!(
+                    "{}: no todo of uid {uid} to depend on",
+                    location(&todos[*index])
+                )
+            }
+            Problem::DependencyCycle { indices } => {
+                let locations: Vec<_> = indices.iter().map(|&i| location(&todos[i])).collect();
+                format!("dependency cycle of {}", locations.join(", "))
+            }
+            Problem::DueBeforePrerequisite {
+                index,
+                prerequisite,
+            } => format!(
+                "{}: due before its prerequisite at {}",
+                location(&todos[*index]),
+                location(&todos[*prerequisite])
+            ),
+        }
+    }
+}
+
+/// All problems of todos.
+pub fn check(todos: &[Todo]) -> Vec<Problem> {
+    let mut problems: Vec<_> = todos
+        .iter()
+        .enumerate()
+        .filter(|(_, todo)| todo.attributes.is_err())
+        .map(|(index, _)| Problem::Malformed { index })
+        .collect();
+    problems.extend(duplicate_uids(todos));
+    problems.extend(
+        missing_dependencies(todos)
+            .into_iter()
+            .map(|(index, uid)| Problem::MissingDependency { index, uid }),
+    );
+    problems.extend(
+        dependency_cycles(todos)
+            .into_iter()
+            .map(|indices| Problem::DependencyCycle { indices }),
+    );
+    problems.extend(due_before_prerequisites(todos));
+    problems
+}
+
+/// Todos due earlier than any of their prerequisites.
+fn due_before_prerequisites(todos: &[Todo]) -> Vec<Problem> {
+    let due = |todo: &Todo| todo.attributes.as_ref().ok()?.due;
+    let mut problems = vec![];
+    for (index, todo) in todos.iter().enumerate() {
+        let Some(own) = due(todo) else {
+            continue;
+        };
+        for &prerequisite in &todo.prerequisites {
+            if due(&todos[prerequisite]).is_some_and(|due| own < due) {
+                problems.push(Problem::DueBeforePrerequisite {
+                    index,
+                    prerequisite,
+                });
+            }
+        }
+    }
+    problems
+}
+
+/// Todos sharing the same uid.
+pub fn duplicate_uids(todos: &[Todo]) -> Vec<Problem> {
+    let mut by_uid: BTreeMap<_, Vec<_>> = BTreeMap::new();
+    for (index, todo) in todos.iter().enumerate() {
+        if let Some(uid) = &todo.uid {
+            by_uid.entry(uid).or_default().push(index);
+        }
+    }
+    by_uid
+        .into_iter()
+        .filter(|(_, indices)| indices.len() > 1)
+        .map(|(uid, indices)| Problem::DuplicateUid {
+            uid: uid.clone(),
+            indices,
+        })
+        .collect()
+}
+
+/// The location of todo as `path:line`.
+pub fn location(todo: &Todo) -> String {
+    format!("{}:{}", todo.metadata.path.display(), todo.metadata.line)
+}
+
+#[cfg(test)]
+mod test {
+    use agemda_core::graph::resolve_dependencies;
+
+    use super::*;
+    use crate::parse::{ParseOptions, parse_text};
+
+    fn problems(text: &str) -> Vec<Problem> {
+        let mut todos = vec![];
+        parse_text(&mut todos, "test.md", text, &ParseOptions::default());
+        resolve_dependencies(&mut todos);
+        check(&todos)
+    }
+
+    #[test]
+    fn no_problem() {
+        assert_eq!(
+            problems(
+                "- [ ] a <agmd:2025-03-09;uid=a>\n\
+                 - [ ] b <agmd:2025-03-10;uid=b;depends=a>\n"
+            ),
+            []
+        );
+    }
+
+    #[test]
+    fn duplicate_and_malformed() {
+        assert_eq!(
+            problems(
+                "- [ ] a <agmd:uid=a>\n\
+                 - [ ] b <agmd:uid=a>\n\
+                 - [ ] c <agmd:x>\n"
+            ),
+            [
+                Problem::Malformed { index: 2 },
+                Problem::DuplicateUid {
+                    uid: "a".to_string(),
+                    indices: vec![0, 1]
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn dependencies() {
+        assert_eq!(
+            problems(
+                "- [ ] a <agmd:2025-03-09;uid=a;depends=c>\n\
+                 - [ ] b <agmd:2025-03-10;uid=b;depends=a,x>\n\
+                 - [ ] c <agmd:2025-03-08;uid=c;depends=b>\n\
+                 - [ ] d <agmd:2025-03-11;uid=d;depends=d>\n"
+            ),
+            [
+                Problem::MissingDependency {
+                    index: 1,
+                    uid: "x".to_string()
+                },
+                Problem::DependencyCycle {
+                    indices: vec![0, 1, 2]
+                },
+                Problem::DependencyCycle { indices: vec![3] },
+                Problem::DueBeforePrerequisite {
+                    index: 2,
+                    prerequisite: 1
+                },
+            ]
+        );
+    }
+}
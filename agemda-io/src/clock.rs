@@ -0,0 +1,121 @@
+use std::ops::Range;
+
+use agemda_core::Clock;
+use chrono::{DateTime, Local, NaiveDateTime, NaiveTime};
+
+use crate::convert::resolve_local;
+
+const DATE_TIME_FORMAT: &str = "%Y-%m-%dT%H:%M";
+const TIME_FORMAT: &str = "%H:%M";
+
+/// Clocks of `CLOCK: start--end` lines in the ranges of text,
+/// optionally as list items, e.g. `- CLOCK: 2025-03-09T10:00--11:30`.
+///
+/// The end is a date time, a time of the start day, or empty while running.
+pub fn clocks(text: &str, ranges: &[Range<usize>]) -> Vec<Clock> {
+    let mut clocks = vec![];
+    for range in ranges {
+        let mut offset = range.start;
+        for line in text[range.clone()].split_inclusive('\n') {
+            if let Some(clock) = clock_line(line, offset) {
+                clocks.push(clock);
+            }
+            offset += line.len();
+        }
+    }
+    clocks
+}
+
+/// Whether every non-blank line in the range of text is a clock line,
+/// e.g. the item `- CLOCK: 2025-03-09T10:00--11:30`.
+pub fn is_clock_block(text: &str, range: &Range<usize>) -> bool {
+    let mut lines = text[range.clone()]
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .peekable();
+    lines.peek().is_some() && lines.all(|line| clock_line(line, 0).is_some())
+}
+
+/// The clock of line, with `offset` of the line in text.
+fn clock_line(line: &str, offset: usize) -> Option<Clock> {
+    let content = line.trim_start();
+    let content = content.strip_prefix("- ").unwrap_or(content);
+    let period = content.strip_prefix("CLOCK:")?.trim();
+    // period is a subslice of line
+    let start_offset = offset + (period.as_ptr() as usize - line.as_ptr() as usize);
+
+    let (start, end) = period.split_once("--")?;
+    let start = NaiveDateTime::parse_from_str(start, DATE_TIME_FORMAT).ok()?;
+    let end = match end {
+        "" => None,
+        end => Some(
+            NaiveDateTime::parse_from_str(end, DATE_TIME_FORMAT)
+                .or_else(|_| {
+                    NaiveTime::parse_from_str(end, TIME_FORMAT)
+                        .map(|time| start.date().and_time(time))
+                })
+                .ok()?,
+        ),
+    };
+    let local = |date_time: NaiveDateTime| resolve_local(&Local, date_time);
+    Some(Clock {
+        start: local(start)?,
+        end: match end {
+            Some(end) => Some(local(end)?),
+            None => None,
+        },
+        range: start_offset..start_offset + period.len(),
+    })
+}
+
+/// Format the period of clock, with only the time of end on the same day.
+pub fn format_period(start: DateTime<Local>, end: Option<DateTime<Local>>) -> String {
+    let start_text = start.format(DATE_TIME_FORMAT);
+    match end {
+        None => format!("{start_text}--"),
+        Some(end) if end.date_naive() == start.date_naive() => {
+            format!("{start_text}--{}", end.format(TIME_FORMAT))
+        }
+        Some(end) => format!("{start_text}--{}", end.format(DATE_TIME_FORMAT)),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::convert::of_second;
+
+    use super::*;
+
+    #[test]
+    fn parse() {
+        let text = "x\n  CLOCK: 2025-03-09T10:00--11:30\n  - CLOCK: 2025-03-09T23:00--2025-03-10T01:00 \n  CLOCK: 2025-03-10T09:00--\n  CLOCK: later\n";
+        // ranges as of a description split by a nested list
+        let ranges = vec![2..35, 35..text.len()];
+        let clocks = clocks(text, &ranges);
+        assert_eq!(clocks.len(), 3);
+        assert_eq!(clocks[0].start, of_second(2025, 3, 9, 10, 0, 0).unwrap());
+        assert_eq!(clocks[0].end, of_second(2025, 3, 9, 11, 30, 0));
+        assert_eq!(&text[clocks[0].range.clone()], "2025-03-09T10:00--11:30");
+        assert_eq!(clocks[1].end, of_second(2025, 3, 10, 1, 0, 0));
+        assert_eq!(
+            &text[clocks[1].range.clone()],
+            "2025-03-09T23:00--2025-03-10T01:00"
+        );
+        assert_eq!(clocks[2].end, None);
+        assert_eq!(&text[clocks[2].range.clone()], "2025-03-10T09:00--");
+    }
+
+    #[test]
+    fn format() {
+        let start = of_second(2025, 3, 9, 10, 0, 0).unwrap();
+        assert_eq!(format_period(start, None), "2025-03-09T10:00--");
+        assert_eq!(
+            format_period(start, of_second(2025, 3, 9, 11, 30, 0)),
+            "2025-03-09T10:00--11:30"
+        );
+        assert_eq!(
+            format_period(start, of_second(2025, 3, 10, 1, 0, 0)),
+            "2025-03-09T10:00--2025-03-10T01:00"
+        );
+    }
+}
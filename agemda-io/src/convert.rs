@@ -1,74 +1,104 @@
-use chrono::{DateTime, Days, Local, Months, NaiveDate, NaiveDateTime, NaiveTime, TimeDelta};
+use chrono::{
+    DateTime, Datelike, Days, FixedOffset, Local, LocalResult, Months, NaiveDate, NaiveDateTime,
+    NaiveTime, Offset as _, TimeDelta, TimeZone, Timelike, Weekday,
+};
 
-use crate::fragment::DateTimeFragment;
+use crate::fragment::{Anchor, DateTimeFragment, Offset, OffsetUnit, Quarter, WeekDate};
 
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum Role {
     Start,
     End,
 }
 
 impl Role {
-    pub fn of_year(&self, year: i32) -> Option<DateTime<Local>> {
+    pub fn of_year<Tz: TimeZone>(&self, tz: &Tz, year: i32) -> Option<DateTime<Tz>> {
         let year = match self {
             Role::Start => year,
             Role::End => year + 1,
         };
-        let date = NaiveDate::from_ymd_opt(year, 01, 01)?;
-        let time = NaiveTime::from_hms_opt(0, 0, 0)?;
-        NaiveDateTime::new(date, time)
-            .and_local_timezone(Local)
-            .single()
+        midnight(tz, NaiveDate::from_ymd_opt(year, 1, 1)?)
     }
 
-    pub fn of_month(&self, year: i32, month: u32) -> Option<DateTime<Local>> {
-        let date = NaiveDate::from_ymd_opt(year, month, 01)?;
-        let time = NaiveTime::from_hms_opt(0, 0, 0)?;
-        let start = NaiveDateTime::new(date, time)
-            .and_local_timezone(Local)
-            .single()?;
+    pub fn of_month<Tz: TimeZone>(&self, tz: &Tz, year: i32, month: u32) -> Option<DateTime<Tz>> {
+        let date = NaiveDate::from_ymd_opt(year, month, 1)?;
         match self {
-            Role::Start => Some(start),
-            Role::End => start.checked_add_months(Months::new(1)),
+            Role::Start => midnight(tz, date),
+            Role::End => midnight(tz, date.checked_add_months(Months::new(1))?),
+        }
+    }
+
+    /// The ISO week, from Monday to Monday.
+    pub fn of_week<Tz: TimeZone>(&self, tz: &Tz, year: i32, week: u32) -> Option<DateTime<Tz>> {
+        let date = NaiveDate::from_isoywd_opt(year, week, Weekday::Mon)?;
+        match self {
+            Role::Start => midnight(tz, date),
+            Role::End => midnight(tz, date.checked_add_days(Days::new(7))?),
+        }
+    }
+
+    pub fn of_quarter<Tz: TimeZone>(
+        &self,
+        tz: &Tz,
+        year: i32,
+        quarter: u32,
+    ) -> Option<DateTime<Tz>> {
+        if !(1..=4).contains(&quarter) {
+            return None;
+        }
+        let date = NaiveDate::from_ymd_opt(year, quarter * 3 - 2, 1)?;
+        match self {
+            Role::Start => midnight(tz, date),
+            Role::End => midnight(tz, date.checked_add_months(Months::new(3))?),
         }
     }
 
-    pub fn of_day(&self, year: i32, month: u32, day: u32) -> Option<DateTime<Local>> {
+    pub fn of_day<Tz: TimeZone>(
+        &self,
+        tz: &Tz,
+        year: i32,
+        month: u32,
+        day: u32,
+    ) -> Option<DateTime<Tz>> {
         let date = NaiveDate::from_ymd_opt(year, month, day)?;
-        let time = NaiveTime::from_hms_opt(0, 0, 0)?;
-        let start = NaiveDateTime::new(date, time)
-            .and_local_timezone(Local)
-            .single()?;
         match self {
-            Role::Start => Some(start),
-            Role::End => start.checked_add_days(Days::new(1)),
+            Role::Start => midnight(tz, date),
+            Role::End => midnight(tz, date.succ_opt()?),
         }
     }
 
-    pub fn of_hour(&self, year: i32, month: u32, day: u32, hour: u32) -> Option<DateTime<Local>> {
+    pub fn of_hour<Tz: TimeZone>(
+        &self,
+        tz: &Tz,
+        year: i32,
+        month: u32,
+        day: u32,
+        hour: u32,
+        offset: Option<FixedOffset>,
+    ) -> Option<DateTime<Tz>> {
         let date = NaiveDate::from_ymd_opt(year, month, day)?;
         let time = NaiveTime::from_hms_opt(hour, 0, 0)?;
-        let start = NaiveDateTime::new(date, time)
-            .and_local_timezone(Local)
-            .single()?;
+        let start = instant(tz, NaiveDateTime::new(date, time), offset)?;
         match self {
             Role::Start => Some(start),
             Role::End => start.checked_add_signed(TimeDelta::hours(1)),
         }
     }
 
-    pub fn of_minute(
+    #[allow(clippy::too_many_arguments)]
+    pub fn of_minute<Tz: TimeZone>(
         &self,
+        tz: &Tz,
         year: i32,
         month: u32,
         day: u32,
         hour: u32,
         minute: u32,
-    ) -> Option<DateTime<Local>> {
+        offset: Option<FixedOffset>,
+    ) -> Option<DateTime<Tz>> {
         let date = NaiveDate::from_ymd_opt(year, month, day)?;
         let time = NaiveTime::from_hms_opt(hour, minute, 0)?;
-        let start = NaiveDateTime::new(date, time)
-            .and_local_timezone(Local)
-            .single()?;
+        let start = instant(tz, NaiveDateTime::new(date, time), offset)?;
         match self {
             Role::Start => Some(start),
             Role::End => start.checked_add_signed(TimeDelta::minutes(1)),
@@ -84,19 +114,183 @@ pub fn of_second(
     minute: u32,
     second: u32,
 ) -> Option<DateTime<Local>> {
-    let date = NaiveDate::from_ymd_opt(year, month, day)?;
     let time = NaiveTime::from_hms_opt(hour, minute, second)?;
-    NaiveDateTime::new(date, time)
-        .and_local_timezone(Local)
-        .single()
+    of_time(&Local, year, month, day, time, None)
+}
+
+/// The exact instant of the date and time, to the fraction of second.
+pub fn of_time<Tz: TimeZone>(
+    tz: &Tz,
+    year: i32,
+    month: u32,
+    day: u32,
+    time: NaiveTime,
+    offset: Option<FixedOffset>,
+) -> Option<DateTime<Tz>> {
+    let date = NaiveDate::from_ymd_opt(year, month, day)?;
+    instant(tz, NaiveDateTime::new(date, time), offset)
 }
 
+/// The instant of the date time in the offset if any, otherwise in the time zone.
+fn instant<Tz: TimeZone>(
+    tz: &Tz,
+    date_time: NaiveDateTime,
+    offset: Option<FixedOffset>,
+) -> Option<DateTime<Tz>> {
+    match offset {
+        Some(offset) => offset
+            .from_local_datetime(&date_time)
+            .single()
+            .map(|date_time| date_time.with_timezone(tz)),
+        None => resolve_local(tz, date_time),
+    }
+}
+
+/// The start of the date in the time zone.
+fn midnight<Tz: TimeZone>(tz: &Tz, date: NaiveDate) -> Option<DateTime<Tz>> {
+    resolve_local(tz, NaiveDateTime::new(date, NaiveTime::MIN))
+}
+
+/// The instant of the local date time in the time zone.
+///
+/// A time skipped by a DST gap is shifted forward by the length of the gap,
+/// e.g. 02:30 is 03:30 when clocks jump from 02:00 to 03:00,
+/// and a time repeated by an overlap is the earliest of the two.
+pub fn resolve_local<Tz: TimeZone>(tz: &Tz, date_time: NaiveDateTime) -> Option<DateTime<Tz>> {
+    match tz.from_local_datetime(&date_time) {
+        LocalResult::Single(resolved) => Some(resolved),
+        LocalResult::Ambiguous(earliest, _) => Some(earliest),
+        LocalResult::None => {
+            // read the time with the offset in effect before the gap
+            let before = tz
+                .from_local_datetime(&date_time.checked_sub_signed(TimeDelta::days(1))?)
+                .earliest()?;
+            let utc = date_time.checked_sub_signed(TimeDelta::seconds(
+                before.offset().fix().local_minus_utc().into(),
+            ))?;
+            Some(tz.from_utc_datetime(&utc))
+        }
+    }
+}
+
+/// Shift the date time by the offset, days and larger keeping the local time.
+pub fn add_offset<Tz: TimeZone>(date_time: DateTime<Tz>, offset: &Offset) -> Option<DateTime<Tz>> {
+    let amount = offset.amount.unsigned_abs();
+    let forward = offset.amount >= 0;
+    let local = date_time.naive_local();
+    let local = match offset.unit {
+        OffsetUnit::Hour => {
+            return date_time.checked_add_signed(TimeDelta::hours(offset.amount.into()));
+        }
+        OffsetUnit::Day | OffsetUnit::Week => {
+            let days = match offset.unit {
+                OffsetUnit::Week => Days::new(u64::from(amount) * 7),
+                _ => Days::new(amount.into()),
+            };
+            if forward {
+                local.checked_add_days(days)
+            } else {
+                local.checked_sub_days(days)
+            }
+        }
+        OffsetUnit::Month | OffsetUnit::Year => {
+            let months = match offset.unit {
+                OffsetUnit::Year => Months::new(amount.checked_mul(12)?),
+                _ => Months::new(amount),
+            };
+            if forward {
+                local.checked_add_months(months)
+            } else {
+                local.checked_sub_months(months)
+            }
+        }
+    }?;
+    resolve_local(&date_time.timezone(), local)
+}
+
+/// The day of anchor relative to the date.
+pub fn anchor_date(anchor: Anchor, date: NaiveDate, week_start: Weekday) -> Option<NaiveDate> {
+    let days_into_week = |weekday: Weekday| {
+        (7 + weekday.num_days_from_monday() - week_start.num_days_from_monday()) % 7
+    };
+    let first_of_week = date.week(week_start).first_day();
+    match anchor {
+        Anchor::Weekday(weekday) => {
+            let days =
+                (7 + weekday.num_days_from_monday() - date.weekday().num_days_from_monday()) % 7;
+            date.checked_add_days(Days::new(days.into()))
+        }
+        Anchor::NextWeekday(weekday) => {
+            first_of_week.checked_add_days(Days::new((7 + days_into_week(weekday)).into()))
+        }
+        Anchor::EndOfWeek => first_of_week.checked_add_days(Days::new(6)),
+        Anchor::EndOfMonth => last_day_of_month(date.year(), date.month()),
+        Anchor::EndOfQuarter => last_day_of_month(date.year(), date.month0() / 3 * 3 + 3),
+        Anchor::EndOfYear => NaiveDate::from_ymd_opt(date.year(), 12, 31),
+    }
+}
+
+fn last_day_of_month(year: i32, month: u32) -> Option<NaiveDate> {
+    NaiveDate::from_ymd_opt(year, month, 1)?
+        .checked_add_months(Months::new(1))?
+        .pred_opt()
+}
+
+/// The reference of resolving named anchors.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Reference {
+    /// The date of anchors without base, usually the load date.
+    pub today: NaiveDate,
+    /// The first day of week, for `eow` and `next-` weekdays.
+    pub week_start: Weekday,
+}
+
+impl Default for Reference {
+    fn default() -> Self {
+        Self {
+            today: Local::now().date_naive(),
+            week_start: Weekday::Mon,
+        }
+    }
+}
+
+/// Resolve the relative fragment over the base, an offset measured from base of the same role.
+///
+/// An anchor is a day, resolved against the date of base or today.
 pub fn fragment_to_datetime(
     relative: &Option<DateTimeFragment>,
     base: &Option<DateTimeFragment>,
     role: Role,
+    reference: &Reference,
 ) -> Option<DateTime<Local>> {
+    let anchor_fragment = |anchor, date| {
+        let date = anchor_date(anchor, date, reference.week_start)?;
+        Some(DateTimeFragment::from_ymd(
+            date.year(),
+            date.month(),
+            date.day(),
+        ))
+    };
+    let concrete_base;
+    let base = match base {
+        Some(DateTimeFragment::Anchor(anchor)) => {
+            concrete_base = Some(anchor_fragment(*anchor, reference.today)?);
+            &concrete_base
+        }
+        _ => base,
+    };
     match (relative, base) {
+        (Some(DateTimeFragment::Anchor(anchor)), base) => {
+            let date = match base {
+                Some(base) => fragment_to_datetime_either(base, Role::Start)?.date_naive(),
+                None => reference.today,
+            };
+            let relative = anchor_fragment(*anchor, date)?;
+            fragment_to_datetime_either(&relative, role)
+        }
+        (Some(DateTimeFragment::Offset(offset)), base) => {
+            add_offset(fragment_to_datetime(&None, base, role, reference)?, offset)
+        }
         (None, None) => None,
         (None, Some(either)) | (Some(either), None) => fragment_to_datetime_either(either, role),
         (Some(relative), Some(base)) => fragment_to_datetime_both(relative, base, role),
@@ -107,52 +301,163 @@ pub fn fragment_to_datetime_either(
     either: &DateTimeFragment,
     role: Role,
 ) -> Option<DateTime<Local>> {
+    match either {
+        DateTimeFragment::Week(WeekDate {
+            year,
+            week,
+            weekday: None,
+        }) => return role.of_week(&Local, *year, *week),
+        DateTimeFragment::Quarter(Quarter { year, quarter }) => {
+            return role.of_quarter(&Local, *year, *quarter);
+        }
+        _ => {}
+    }
     let year = either.year()?;
     let Some(month) = either.month() else {
-        return role.of_year(year);
+        return role.of_year(&Local, year);
     };
     let Some(day) = either.day() else {
-        return role.of_month(year, month);
+        return role.of_month(&Local, year, month);
     };
     let Some(hour) = either.hour() else {
-        return role.of_day(year, month, day);
+        return role.of_day(&Local, year, month, day);
     };
+    let offset = either.offset();
     let Some(minute) = either.minute() else {
-        return role.of_hour(year, month, day, hour);
+        return role.of_hour(&Local, year, month, day, hour, offset);
     };
     let Some(second) = either.second() else {
-        return role.of_minute(year, month, day, hour, minute);
+        return role.of_minute(&Local, year, month, day, hour, minute, offset);
     };
-    of_second(year, month, day, hour, minute, second)
+    let nanosecond = either.nanosecond().unwrap_or_default();
+    let time = NaiveTime::from_hms_nano_opt(hour, minute, second, nanosecond)?;
+    of_time(&Local, year, month, day, time, offset)
 }
 
+/// Resolve due over the base, an offset measured from the instant of start if any,
+/// due the end of the day it reaches, or of the hour for an offset of hours.
+///
+/// E.g. `start=2025-03-09;due=+3d` is due the end of 12th,
+/// and `2025-03;start=+1w;due=+3d` the end of 11th.
+pub fn due_to_datetime(
+    due: &Option<DateTimeFragment>,
+    start: &Option<DateTimeFragment>,
+    base: &Option<DateTimeFragment>,
+    reference: &Reference,
+) -> Option<DateTime<Local>> {
+    match (due, start) {
+        (Some(DateTimeFragment::Offset(offset)), Some(_)) => {
+            let start = fragment_to_datetime(start, base, Role::Start, reference)?;
+            let reached = add_offset(start, offset)?.naive_local();
+            let (year, month, day) = (reached.year(), reached.month(), reached.day());
+            match offset.unit {
+                OffsetUnit::Hour => {
+                    Role::End.of_hour(&Local, year, month, day, reached.hour(), None)
+                }
+                _ => Role::End.of_day(&Local, year, month, day),
+            }
+        }
+        _ => fragment_to_datetime(due, base, Role::End, reference),
+    }
+}
+
+/// Resolve the relative fragment over the base, fields missing in relative taken from base.
+///
+/// A bare two digits `MonthOrDay` is a day when base has a month, otherwise a month.
+/// A day without month over a whole week is the day of that week, if any,
+/// e.g. `12` over `2025-W11` is 2025-03-12 and `02` over `2025-W14` is 2025-04-02,
+/// and one with month takes the year of its Monday.
+/// A bare two digits over a quarter is ambiguous, so unresolvable.
+/// Fields of relative finer than a field missing in both make it unresolvable,
+/// e.g. day `12` over year `2025`.
 pub fn fragment_to_datetime_both(
     relative: &DateTimeFragment,
     base: &DateTimeFragment,
     role: Role,
 ) -> Option<DateTime<Local>> {
+    if let DateTimeFragment::Week(WeekDate {
+        year,
+        week,
+        weekday: None,
+    }) = base
+        && (relative.day().is_some() || matches!(relative, DateTimeFragment::MonthOrDay(_)))
+    {
+        let monday = NaiveDate::from_isoywd_opt(*year, *week, Weekday::Mon)?;
+        let bare_day = match relative {
+            DateTimeFragment::MonthOrDay(day) => Some(*day),
+            _ => relative.day().filter(|_| relative.month().is_none()),
+        };
+        let date = match bare_day {
+            Some(day) => monday.iter_days().take(7).find(|date| date.day() == day)?,
+            None => monday,
+        };
+        let base = DateTimeFragment::from_ym(date.year(), date.month());
+        return fragment_to_datetime_both(relative, &base, role);
+    }
+    if let (DateTimeFragment::MonthOrDay(_), DateTimeFragment::Quarter(_)) = (relative, base) {
+        return None;
+    }
+    if let DateTimeFragment::MonthOrDay(month_or_day) = relative {
+        let relative = if base.month().is_some() {
+            DateTimeFragment::from_d(*month_or_day)
+        } else {
+            DateTimeFragment::from_m(*month_or_day)
+        };
+        return fragment_to_datetime_both(&relative, base, role);
+    }
+    // whole weeks and quarters are not fields of calendar to take from base
+    if let DateTimeFragment::Week(WeekDate { weekday: None, .. }) | DateTimeFragment::Quarter(_) =
+        relative
+    {
+        return fragment_to_datetime_either(relative, role);
+    }
+
     let year = relative.year().or(base.year())?;
     let Some(month) = relative.month().or(base.month()) else {
-        return role.of_year(year);
+        return role
+            .of_year(&Local, year)
+            .filter(|_| relative.day().is_none() && relative.hour().is_none());
     };
     let Some(day) = relative.day().or(base.day()) else {
-        return role.of_month(year, month);
+        return role
+            .of_month(&Local, year, month)
+            .filter(|_| relative.hour().is_none());
     };
     let Some(hour) = relative.hour().or(base.hour()) else {
-        return role.of_day(year, month, day);
+        return role.of_day(&Local, year, month, day);
+    };
+    // the offset goes with the time it is written on
+    let offset = match relative.hour() {
+        Some(_) => relative.offset(),
+        None => base.offset(),
     };
     let Some(minute) = relative.minute().or(base.minute()) else {
-        return role.of_hour(year, month, day, hour);
+        return role.of_hour(&Local, year, month, day, hour, offset);
     };
     let Some(second) = relative.second().or(base.second()) else {
-        return role.of_minute(year, month, day, hour, minute);
+        return role.of_minute(&Local, year, month, day, hour, minute, offset);
     };
-    of_second(year, month, day, hour, minute, second)
+    let nanosecond = match relative.second() {
+        Some(_) => relative.nanosecond(),
+        None => base.nanosecond(),
+    };
+    let time = NaiveTime::from_hms_nano_opt(hour, minute, second, nanosecond.unwrap_or_default())?;
+    of_time(&Local, year, month, day, time, offset)
 }
 
 #[cfg(test)]
+#[allow(clippy::zero_prefixed_literal)]
 pub mod test {
     use super::*;
+    use crate::fragment::date_time_fragment;
+
+    /// Wednesday 2025-03-12 with weeks starting on Monday.
+    fn reference() -> Reference {
+        Reference {
+            today: NaiveDate::from_ymd_opt(2025, 03, 12).unwrap(),
+            week_start: Weekday::Mon,
+        }
+    }
 
     macro_rules! case {
         ($name:ident, $relative:expr, $base:expr, $start:expr, $end:expr $(,)?) => {
@@ -161,13 +466,14 @@ pub mod test {
 
                 #[test]
                 pub fn start() {
-                    let result = fragment_to_datetime(&$relative, &$base, Role::Start);
+                    let result =
+                        fragment_to_datetime(&$relative, &$base, Role::Start, &reference());
                     assert_eq!(result, $start);
                 }
 
                 #[test]
                 pub fn end() {
-                    let result = fragment_to_datetime(&$relative, &$base, Role::End);
+                    let result = fragment_to_datetime(&$relative, &$base, Role::End, &reference());
                     assert_eq!(result, $end);
                 }
             }
@@ -257,7 +563,6 @@ pub mod test {
     );
 
     // both
-    // TODO: more both test case
     case!(
         both_relative_year_base_year,
         Some(DateTimeFragment::from_y(2025)),
@@ -272,4 +577,643 @@ pub mod test {
         of_second(2025, 01, 01, 00, 00, 00),
         of_second(2026, 01, 01, 00, 00, 00),
     );
+    // base precision by relative variant
+    case!(
+        both_relative_year_base_year_month,
+        Some(DateTimeFragment::from_y(2025)),
+        Some(DateTimeFragment::from_ym(2024, 05)),
+        of_second(2025, 05, 01, 00, 00, 00),
+        of_second(2025, 06, 01, 00, 00, 00),
+    );
+    case!(
+        both_relative_year_base_year_month_day,
+        Some(DateTimeFragment::from_y(2025)),
+        Some(DateTimeFragment::from_ymd(2024, 05, 06)),
+        of_second(2025, 05, 06, 00, 00, 00),
+        of_second(2025, 05, 07, 00, 00, 00),
+    );
+    case!(
+        both_relative_year_base_hour,
+        Some(DateTimeFragment::from_y(2025)),
+        Some(DateTimeFragment::from_ymd_h(2024, 05, 06, 07)),
+        of_second(2025, 05, 06, 07, 00, 00),
+        of_second(2025, 05, 06, 08, 00, 00),
+    );
+    case!(
+        both_relative_year_base_minute,
+        Some(DateTimeFragment::from_y(2025)),
+        Some(DateTimeFragment::from_ymd_hm(2024, 05, 06, 07, 08)),
+        of_second(2025, 05, 06, 07, 08, 00),
+        of_second(2025, 05, 06, 07, 09, 00),
+    );
+    case!(
+        both_relative_year_base_second,
+        Some(DateTimeFragment::from_y(2025)),
+        Some(DateTimeFragment::from_ymd_hms(2024, 05, 06, 07, 08, 09)),
+        of_second(2025, 05, 06, 07, 08, 09),
+        of_second(2025, 05, 06, 07, 08, 09),
+    );
+    case!(
+        both_relative_month_or_day_base_year,
+        Some(DateTimeFragment::from_m_d(10)),
+        Some(DateTimeFragment::from_y(2024)),
+        of_second(2024, 10, 01, 00, 00, 00),
+        of_second(2024, 11, 01, 00, 00, 00),
+    );
+    case!(
+        both_relative_month_or_day_base_year_month,
+        Some(DateTimeFragment::from_m_d(10)),
+        Some(DateTimeFragment::from_ym(2024, 05)),
+        of_second(2024, 05, 10, 00, 00, 00),
+        of_second(2024, 05, 11, 00, 00, 00),
+    );
+    case!(
+        both_relative_month_or_day_base_year_month_day,
+        Some(DateTimeFragment::from_m_d(10)),
+        Some(DateTimeFragment::from_ymd(2024, 05, 06)),
+        of_second(2024, 05, 10, 00, 00, 00),
+        of_second(2024, 05, 11, 00, 00, 00),
+    );
+    case!(
+        both_relative_month_or_day_base_hour,
+        Some(DateTimeFragment::from_m_d(10)),
+        Some(DateTimeFragment::from_ymd_h(2024, 05, 06, 07)),
+        of_second(2024, 05, 10, 07, 00, 00),
+        of_second(2024, 05, 10, 08, 00, 00),
+    );
+    case!(
+        both_relative_month_or_day_base_minute,
+        Some(DateTimeFragment::from_m_d(10)),
+        Some(DateTimeFragment::from_ymd_hm(2024, 05, 06, 07, 08)),
+        of_second(2024, 05, 10, 07, 08, 00),
+        of_second(2024, 05, 10, 07, 09, 00),
+    );
+    case!(
+        both_relative_month_or_day_base_second,
+        Some(DateTimeFragment::from_m_d(10)),
+        Some(DateTimeFragment::from_ymd_hms(2024, 05, 06, 07, 08, 09)),
+        of_second(2024, 05, 10, 07, 08, 09),
+        of_second(2024, 05, 10, 07, 08, 09),
+    );
+    case!(
+        both_relative_month_base_year,
+        Some(DateTimeFragment::from_m(10)),
+        Some(DateTimeFragment::from_y(2024)),
+        of_second(2024, 10, 01, 00, 00, 00),
+        of_second(2024, 11, 01, 00, 00, 00),
+    );
+    case!(
+        both_relative_month_base_year_month,
+        Some(DateTimeFragment::from_m(10)),
+        Some(DateTimeFragment::from_ym(2024, 05)),
+        of_second(2024, 10, 01, 00, 00, 00),
+        of_second(2024, 11, 01, 00, 00, 00),
+    );
+    case!(
+        both_relative_month_base_year_month_day,
+        Some(DateTimeFragment::from_m(10)),
+        Some(DateTimeFragment::from_ymd(2024, 05, 06)),
+        of_second(2024, 10, 06, 00, 00, 00),
+        of_second(2024, 10, 07, 00, 00, 00),
+    );
+    case!(
+        both_relative_month_base_hour,
+        Some(DateTimeFragment::from_m(10)),
+        Some(DateTimeFragment::from_ymd_h(2024, 05, 06, 07)),
+        of_second(2024, 10, 06, 07, 00, 00),
+        of_second(2024, 10, 06, 08, 00, 00),
+    );
+    case!(
+        both_relative_month_base_minute,
+        Some(DateTimeFragment::from_m(10)),
+        Some(DateTimeFragment::from_ymd_hm(2024, 05, 06, 07, 08)),
+        of_second(2024, 10, 06, 07, 08, 00),
+        of_second(2024, 10, 06, 07, 09, 00),
+    );
+    case!(
+        both_relative_month_base_second,
+        Some(DateTimeFragment::from_m(10)),
+        Some(DateTimeFragment::from_ymd_hms(2024, 05, 06, 07, 08, 09)),
+        of_second(2024, 10, 06, 07, 08, 09),
+        of_second(2024, 10, 06, 07, 08, 09),
+    );
+    case!(
+        both_relative_day_base_year,
+        Some(DateTimeFragment::from_d(12)),
+        Some(DateTimeFragment::from_y(2024)),
+        None,
+        None,
+    );
+    case!(
+        both_relative_day_base_year_month,
+        Some(DateTimeFragment::from_d(12)),
+        Some(DateTimeFragment::from_ym(2024, 05)),
+        of_second(2024, 05, 12, 00, 00, 00),
+        of_second(2024, 05, 13, 00, 00, 00),
+    );
+    case!(
+        both_relative_day_base_year_month_day,
+        Some(DateTimeFragment::from_d(12)),
+        Some(DateTimeFragment::from_ymd(2024, 05, 06)),
+        of_second(2024, 05, 12, 00, 00, 00),
+        of_second(2024, 05, 13, 00, 00, 00),
+    );
+    case!(
+        both_relative_day_base_hour,
+        Some(DateTimeFragment::from_d(12)),
+        Some(DateTimeFragment::from_ymd_h(2024, 05, 06, 07)),
+        of_second(2024, 05, 12, 07, 00, 00),
+        of_second(2024, 05, 12, 08, 00, 00),
+    );
+    case!(
+        both_relative_day_base_minute,
+        Some(DateTimeFragment::from_d(12)),
+        Some(DateTimeFragment::from_ymd_hm(2024, 05, 06, 07, 08)),
+        of_second(2024, 05, 12, 07, 08, 00),
+        of_second(2024, 05, 12, 07, 09, 00),
+    );
+    case!(
+        both_relative_day_base_second,
+        Some(DateTimeFragment::from_d(12)),
+        Some(DateTimeFragment::from_ymd_hms(2024, 05, 06, 07, 08, 09)),
+        of_second(2024, 05, 12, 07, 08, 09),
+        of_second(2024, 05, 12, 07, 08, 09),
+    );
+    case!(
+        both_relative_hour_base_year,
+        Some(DateTimeFragment::from_h(13)),
+        Some(DateTimeFragment::from_y(2024)),
+        None,
+        None,
+    );
+    case!(
+        both_relative_hour_base_year_month,
+        Some(DateTimeFragment::from_h(13)),
+        Some(DateTimeFragment::from_ym(2024, 05)),
+        None,
+        None,
+    );
+    case!(
+        both_relative_hour_base_year_month_day,
+        Some(DateTimeFragment::from_h(13)),
+        Some(DateTimeFragment::from_ymd(2024, 05, 06)),
+        of_second(2024, 05, 06, 13, 00, 00),
+        of_second(2024, 05, 06, 14, 00, 00),
+    );
+    case!(
+        both_relative_hour_base_hour,
+        Some(DateTimeFragment::from_h(13)),
+        Some(DateTimeFragment::from_ymd_h(2024, 05, 06, 07)),
+        of_second(2024, 05, 06, 13, 00, 00),
+        of_second(2024, 05, 06, 14, 00, 00),
+    );
+    case!(
+        both_relative_hour_base_minute,
+        Some(DateTimeFragment::from_h(13)),
+        Some(DateTimeFragment::from_ymd_hm(2024, 05, 06, 07, 08)),
+        of_second(2024, 05, 06, 13, 08, 00),
+        of_second(2024, 05, 06, 13, 09, 00),
+    );
+    case!(
+        both_relative_hour_base_second,
+        Some(DateTimeFragment::from_h(13)),
+        Some(DateTimeFragment::from_ymd_hms(2024, 05, 06, 07, 08, 09)),
+        of_second(2024, 05, 06, 13, 08, 09),
+        of_second(2024, 05, 06, 13, 08, 09),
+    );
+    // offset
+    case!(
+        offset_without_base,
+        Some(DateTimeFragment::from_offset(3, OffsetUnit::Day)),
+        None,
+        None,
+        None,
+    );
+    case!(
+        offset_days_base_day,
+        Some(DateTimeFragment::from_offset(3, OffsetUnit::Day)),
+        Some(DateTimeFragment::from_ymd(2025, 03, 09)),
+        of_second(2025, 03, 12, 00, 00, 00),
+        of_second(2025, 03, 13, 00, 00, 00),
+    );
+    case!(
+        offset_weeks_base_day,
+        Some(DateTimeFragment::from_offset(-1, OffsetUnit::Week)),
+        Some(DateTimeFragment::from_ymd(2025, 03, 09)),
+        of_second(2025, 03, 02, 00, 00, 00),
+        of_second(2025, 03, 03, 00, 00, 00),
+    );
+    case!(
+        offset_months_base_month,
+        Some(DateTimeFragment::from_offset(-1, OffsetUnit::Month)),
+        Some(DateTimeFragment::from_ym(2025, 03)),
+        of_second(2025, 02, 01, 00, 00, 00),
+        of_second(2025, 03, 01, 00, 00, 00),
+    );
+    case!(
+        offset_years_base_year,
+        Some(DateTimeFragment::from_offset(1, OffsetUnit::Year)),
+        Some(DateTimeFragment::from_y(2025)),
+        of_second(2026, 01, 01, 00, 00, 00),
+        of_second(2027, 01, 01, 00, 00, 00),
+    );
+    case!(
+        offset_hours_base_hour,
+        Some(DateTimeFragment::from_offset(4, OffsetUnit::Hour)),
+        Some(DateTimeFragment::from_ymd_h(2025, 03, 09, 22)),
+        of_second(2025, 03, 10, 02, 00, 00),
+        of_second(2025, 03, 10, 03, 00, 00),
+    );
+
+    #[test]
+    fn due_offset_from_start() {
+        let due = Some(DateTimeFragment::from_offset(3, OffsetUnit::Day));
+        let start = Some(DateTimeFragment::from_ymd(2025, 03, 09));
+        assert_eq!(
+            due_to_datetime(&due, &start, &None, &reference()),
+            of_second(2025, 03, 13, 00, 00, 00)
+        );
+        let base = Some(DateTimeFragment::from_ym(2025, 03));
+        let start = Some(DateTimeFragment::from_offset(1, OffsetUnit::Week));
+        assert_eq!(
+            due_to_datetime(&due, &start, &base, &reference()),
+            of_second(2025, 03, 12, 00, 00, 00)
+        );
+        assert_eq!(
+            due_to_datetime(&due, &None, &base, &reference()),
+            of_second(2025, 04, 04, 00, 00, 00)
+        );
+        let start = Some(DateTimeFragment::from_ymd_hm(2025, 03, 09, 10, 30));
+        assert_eq!(
+            due_to_datetime(&due, &start, &None, &reference()),
+            of_second(2025, 03, 13, 00, 00, 00)
+        );
+        let due = Some(DateTimeFragment::from_offset(4, OffsetUnit::Hour));
+        assert_eq!(
+            due_to_datetime(&due, &start, &None, &reference()),
+            of_second(2025, 03, 09, 15, 00, 00)
+        );
+    }
+    // anchor
+    case!(
+        anchor_weekday_today,
+        Some(DateTimeFragment::from_anchor(Anchor::Weekday(Weekday::Fri))),
+        None,
+        of_second(2025, 03, 14, 00, 00, 00),
+        of_second(2025, 03, 15, 00, 00, 00),
+    );
+    case!(
+        anchor_weekday_same_day,
+        Some(DateTimeFragment::from_anchor(Anchor::Weekday(Weekday::Sun))),
+        Some(DateTimeFragment::from_ymd(2025, 03, 09)),
+        of_second(2025, 03, 09, 00, 00, 00),
+        of_second(2025, 03, 10, 00, 00, 00),
+    );
+    case!(
+        anchor_next_weekday,
+        Some(DateTimeFragment::from_anchor(Anchor::NextWeekday(
+            Weekday::Mon
+        ))),
+        Some(DateTimeFragment::from_ymd(2025, 03, 09)),
+        of_second(2025, 03, 10, 00, 00, 00),
+        of_second(2025, 03, 11, 00, 00, 00),
+    );
+    case!(
+        anchor_end_of_week,
+        Some(DateTimeFragment::from_anchor(Anchor::EndOfWeek)),
+        None,
+        of_second(2025, 03, 16, 00, 00, 00),
+        of_second(2025, 03, 17, 00, 00, 00),
+    );
+    case!(
+        anchor_end_of_month_base_month,
+        Some(DateTimeFragment::from_anchor(Anchor::EndOfMonth)),
+        Some(DateTimeFragment::from_ym(2024, 02)),
+        of_second(2024, 02, 29, 00, 00, 00),
+        of_second(2024, 03, 01, 00, 00, 00),
+    );
+    case!(
+        anchor_end_of_quarter,
+        Some(DateTimeFragment::from_anchor(Anchor::EndOfQuarter)),
+        Some(DateTimeFragment::from_ymd(2025, 11, 20)),
+        of_second(2025, 12, 31, 00, 00, 00),
+        of_second(2026, 01, 01, 00, 00, 00),
+    );
+    case!(
+        anchor_base_with_hour,
+        Some(DateTimeFragment::from_h(17)),
+        Some(DateTimeFragment::from_anchor(Anchor::EndOfMonth)),
+        of_second(2025, 03, 31, 17, 00, 00),
+        of_second(2025, 03, 31, 18, 00, 00),
+    );
+
+    #[test]
+    fn anchor_week_start() {
+        let date = NaiveDate::from_ymd_opt(2025, 03, 09).unwrap();
+        let march = |day| NaiveDate::from_ymd_opt(2025, 03, day);
+        assert_eq!(anchor_date(Anchor::EndOfWeek, date, Weekday::Mon), march(9));
+        assert_eq!(
+            anchor_date(Anchor::EndOfWeek, date, Weekday::Sun),
+            march(15)
+        );
+        assert_eq!(
+            anchor_date(Anchor::NextWeekday(Weekday::Fri), date, Weekday::Sun),
+            march(21)
+        );
+    }
+    // week, ordinal and quarter
+    case!(
+        week,
+        Some(DateTimeFragment::from_yw(2025, 11)),
+        None,
+        of_second(2025, 03, 10, 00, 00, 00),
+        of_second(2025, 03, 17, 00, 00, 00),
+    );
+    case!(
+        week_across_year,
+        Some(DateTimeFragment::from_yw(2026, 01)),
+        None,
+        of_second(2025, 12, 29, 00, 00, 00),
+        of_second(2026, 01, 05, 00, 00, 00),
+    );
+    case!(
+        week_day,
+        Some(DateTimeFragment::from_ywd(2025, 11, 3)),
+        None,
+        of_second(2025, 03, 12, 00, 00, 00),
+        of_second(2025, 03, 13, 00, 00, 00),
+    );
+    case!(
+        ordinal,
+        Some(DateTimeFragment::from_yo(2025, 68)),
+        None,
+        of_second(2025, 03, 09, 00, 00, 00),
+        of_second(2025, 03, 10, 00, 00, 00),
+    );
+    case!(
+        quarter,
+        Some(DateTimeFragment::from_yq(2025, 4)),
+        None,
+        of_second(2025, 10, 01, 00, 00, 00),
+        of_second(2026, 01, 01, 00, 00, 00),
+    );
+    case!(
+        quarter_invalid,
+        Some(DateTimeFragment::from_yq(2025, 5)),
+        None,
+        None,
+        None,
+    );
+    case!(
+        both_relative_week_base_day,
+        Some(DateTimeFragment::from_yw(2025, 12)),
+        Some(DateTimeFragment::from_ymd(2025, 03, 09)),
+        of_second(2025, 03, 17, 00, 00, 00),
+        of_second(2025, 03, 24, 00, 00, 00),
+    );
+    case!(
+        both_relative_day_base_week_day,
+        Some(DateTimeFragment::from_d(14)),
+        Some(DateTimeFragment::from_ywd(2025, 11, 1)),
+        of_second(2025, 03, 14, 00, 00, 00),
+        of_second(2025, 03, 15, 00, 00, 00),
+    );
+    case!(
+        both_relative_hour_base_ordinal,
+        Some(DateTimeFragment::from_h(10)),
+        Some(DateTimeFragment::from_yo(2025, 68)),
+        of_second(2025, 03, 09, 10, 00, 00),
+        of_second(2025, 03, 09, 11, 00, 00),
+    );
+    case!(
+        both_relative_month_day_base_quarter,
+        Some(DateTimeFragment::from_md(05, 15)),
+        Some(DateTimeFragment::from_yq(2025, 2)),
+        of_second(2025, 05, 15, 00, 00, 00),
+        of_second(2025, 05, 16, 00, 00, 00),
+    );
+    // relative variant by whole week base
+    case!(
+        both_relative_year_base_week,
+        Some(DateTimeFragment::from_y(2026)),
+        Some(DateTimeFragment::from_yw(2025, 11)),
+        of_second(2026, 01, 01, 00, 00, 00),
+        of_second(2027, 01, 01, 00, 00, 00),
+    );
+    case!(
+        both_relative_month_or_day_base_week,
+        Some(DateTimeFragment::from_m_d(12)),
+        Some(DateTimeFragment::from_yw(2025, 11)),
+        of_second(2025, 03, 12, 00, 00, 00),
+        of_second(2025, 03, 13, 00, 00, 00),
+    );
+    case!(
+        both_relative_month_or_day_base_week_across_year,
+        Some(DateTimeFragment::from_m_d(31)),
+        Some(DateTimeFragment::from_yw(2026, 01)),
+        of_second(2025, 12, 31, 00, 00, 00),
+        of_second(2026, 01, 01, 00, 00, 00),
+    );
+    case!(
+        both_relative_month_or_day_base_week_across_month,
+        Some(DateTimeFragment::from_m_d(02)),
+        Some(DateTimeFragment::from_yw(2025, 14)),
+        of_second(2025, 04, 02, 00, 00, 00),
+        of_second(2025, 04, 03, 00, 00, 00),
+    );
+    case!(
+        both_relative_month_or_day_base_week_across_year_next,
+        Some(DateTimeFragment::from_m_d(02)),
+        Some(DateTimeFragment::from_yw(2026, 01)),
+        of_second(2026, 01, 02, 00, 00, 00),
+        of_second(2026, 01, 03, 00, 00, 00),
+    );
+    case!(
+        both_relative_month_or_day_base_week_out_of_week,
+        Some(DateTimeFragment::from_m_d(20)),
+        Some(DateTimeFragment::from_yw(2025, 11)),
+        None,
+        None,
+    );
+    case!(
+        both_relative_day_base_week_across_month,
+        Some(DateTimeFragment::from_d_h(31, 13)),
+        Some(DateTimeFragment::from_yw(2025, 14)),
+        of_second(2025, 03, 31, 13, 00, 00),
+        of_second(2025, 03, 31, 14, 00, 00),
+    );
+    case!(
+        both_relative_month_base_week,
+        Some(DateTimeFragment::from_m(10)),
+        Some(DateTimeFragment::from_yw(2025, 11)),
+        of_second(2025, 10, 01, 00, 00, 00),
+        of_second(2025, 11, 01, 00, 00, 00),
+    );
+    case!(
+        both_relative_month_day_base_week,
+        Some(DateTimeFragment::from_md(10, 05)),
+        Some(DateTimeFragment::from_yw(2025, 11)),
+        of_second(2025, 10, 05, 00, 00, 00),
+        of_second(2025, 10, 06, 00, 00, 00),
+    );
+    case!(
+        both_relative_day_base_week,
+        Some(DateTimeFragment::from_d(12)),
+        Some(DateTimeFragment::from_yw(2025, 11)),
+        of_second(2025, 03, 12, 00, 00, 00),
+        of_second(2025, 03, 13, 00, 00, 00),
+    );
+    case!(
+        both_relative_day_hour_base_week,
+        Some(DateTimeFragment::from_d_h(12, 13)),
+        Some(DateTimeFragment::from_yw(2025, 11)),
+        of_second(2025, 03, 12, 13, 00, 00),
+        of_second(2025, 03, 12, 14, 00, 00),
+    );
+    case!(
+        both_relative_hour_base_week,
+        Some(DateTimeFragment::from_h(13)),
+        Some(DateTimeFragment::from_yw(2025, 11)),
+        None,
+        None,
+    );
+    case!(
+        both_relative_week_base_week,
+        Some(DateTimeFragment::from_yw(2025, 12)),
+        Some(DateTimeFragment::from_yw(2025, 11)),
+        of_second(2025, 03, 17, 00, 00, 00),
+        of_second(2025, 03, 24, 00, 00, 00),
+    );
+    case!(
+        both_relative_month_or_day_base_quarter,
+        Some(DateTimeFragment::from_m_d(10)),
+        Some(DateTimeFragment::from_yq(2025, 2)),
+        None,
+        None,
+    );
+    case!(
+        anchor_base_week,
+        Some(DateTimeFragment::from_anchor(Anchor::Weekday(Weekday::Fri))),
+        Some(DateTimeFragment::from_yw(2025, 11)),
+        of_second(2025, 03, 14, 00, 00, 00),
+        of_second(2025, 03, 15, 00, 00, 00),
+    );
+
+    fn rfc3339(text: &str) -> Option<DateTime<Local>> {
+        DateTime::parse_from_rfc3339(text)
+            .ok()
+            .map(|date_time| date_time.with_timezone(&Local))
+    }
+
+    // time offset
+    case!(
+        offset_utc_hour,
+        Some(date_time_fragment("2025-03-09T10Z").unwrap().1),
+        None,
+        rfc3339("2025-03-09T10:00:00Z"),
+        rfc3339("2025-03-09T11:00:00Z"),
+    );
+    case!(
+        offset_fraction,
+        Some(
+            date_time_fragment("2025-03-09T10:20:30.250-08:00")
+                .unwrap()
+                .1
+        ),
+        None,
+        rfc3339("2025-03-09T10:20:30.250-08:00"),
+        rfc3339("2025-03-09T10:20:30.250-08:00"),
+    );
+    case!(
+        offset_of_base_time,
+        Some(DateTimeFragment::from_ymd(2025, 03, 10)),
+        Some(date_time_fragment("2025-03-09T10:20+05:30").unwrap().1),
+        rfc3339("2025-03-10T10:20:00+05:30"),
+        rfc3339("2025-03-10T10:21:00+05:30"),
+    );
+    case!(
+        offset_not_of_relative_time,
+        Some(DateTimeFragment::from_h(12)),
+        Some(date_time_fragment("2025-03-09T10Z").unwrap().1),
+        of_second(2025, 03, 09, 12, 00, 00),
+        of_second(2025, 03, 09, 13, 00, 00),
+    );
+
+    mod dst {
+        use chrono::Utc;
+        use chrono_tz::{America::New_York, America::Santiago, Tz};
+
+        use super::*;
+
+        fn naive(text: &str) -> NaiveDateTime {
+            NaiveDateTime::parse_from_str(text, "%Y-%m-%dT%H:%M").unwrap()
+        }
+
+        fn utc(tz: Option<DateTime<Tz>>) -> Option<String> {
+            tz.map(|date_time| {
+                date_time
+                    .with_timezone(&Utc)
+                    .format("%FT%H:%MZ")
+                    .to_string()
+            })
+        }
+
+        #[test]
+        fn gap_shifts_forward() {
+            // clocks jump from 02:00 to 03:00 EDT
+            let resolved = resolve_local(&New_York, naive("2025-03-09T02:30"));
+            assert_eq!(utc(resolved), Some("2025-03-09T07:30Z".to_string()));
+            assert_eq!(
+                resolved.map(|date_time| date_time.naive_local()),
+                Some(naive("2025-03-09T03:30"))
+            );
+            let start = Role::Start.of_hour(&New_York, 2025, 03, 09, 02, None);
+            let end = Role::End.of_hour(&New_York, 2025, 03, 09, 02, None);
+            assert_eq!(utc(start), Some("2025-03-09T07:00Z".to_string()));
+            assert_eq!(utc(end), Some("2025-03-09T08:00Z".to_string()));
+        }
+
+        #[test]
+        fn overlap_takes_earliest() {
+            // clocks fall back from 02:00 EDT to 01:00 EST
+            let resolved = resolve_local(&New_York, naive("2025-11-02T01:30"));
+            assert_eq!(utc(resolved), Some("2025-11-02T05:30Z".to_string()));
+        }
+
+        #[test]
+        fn day_ends_at_next_midnight() {
+            let end = Role::End.of_day(&New_York, 2025, 03, 09);
+            assert_eq!(utc(end), Some("2025-03-10T04:00Z".to_string()));
+            let end = Role::End.of_month(&New_York, 2025, 10);
+            assert_eq!(utc(end), Some("2025-11-01T04:00Z".to_string()));
+            let end = Role::End.of_week(&New_York, 2025, 44);
+            assert_eq!(utc(end), Some("2025-11-03T05:00Z".to_string()));
+        }
+
+        #[test]
+        fn midnight_in_gap() {
+            // clocks jump from 00:00 to 01:00 -03 on 2025-09-07
+            let start = Role::Start.of_day(&Santiago, 2025, 09, 07);
+            let end = Role::End.of_day(&Santiago, 2025, 09, 06);
+            assert_eq!(utc(start), Some("2025-09-07T04:00Z".to_string()));
+            assert_eq!(start, end);
+        }
+
+        #[test]
+        fn offset_keeps_local_time() {
+            let date_time = resolve_local(&New_York, naive("2025-03-08T10:00")).unwrap();
+            let offset = Offset {
+                amount: 1,
+                unit: OffsetUnit::Day,
+            };
+            let shifted = add_offset(date_time, &offset);
+            assert_eq!(utc(shifted), Some("2025-03-09T14:00Z".to_string()));
+            let offset = Offset {
+                amount: 24,
+                unit: OffsetUnit::Hour,
+            };
+            let shifted = add_offset(date_time, &offset);
+            assert_eq!(utc(shifted), Some("2025-03-09T15:00Z".to_string()));
+        }
+    }
 }
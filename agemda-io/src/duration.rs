@@ -0,0 +1,139 @@
+use chrono::TimeDelta;
+use nom::{
+    IResult, Parser,
+    bytes::complete::tag,
+    character::complete::{digit1, one_of},
+    combinator::{opt, verify},
+    sequence::{preceded, terminated},
+};
+
+/// Parse ISO 8601 duration of weeks, days, hours, minutes and seconds,
+/// with optional sign, e.g. `-PT15M`, `P1DT12H` or `P2W`.
+///
+/// Years and months are not supported, as their length varies,
+/// and a duration out of the range of `TimeDelta` is an error.
+pub fn duration(input: &str) -> IResult<&str, TimeDelta> {
+    let (input, sign) = opt(one_of("+-")).parse(input)?;
+    let (input, _) = tag("P")(input)?;
+    let (input, weeks) = opt(terminated(number, tag("W"))).parse(input)?;
+    let (input, days) = opt(terminated(number, tag("D"))).parse(input)?;
+    let (input, time) = opt(preceded(
+        tag("T"),
+        verify(
+            (
+                opt(terminated(number, tag("H"))),
+                opt(terminated(number, tag("M"))),
+                opt(terminated(number, tag("S"))),
+            ),
+            |(h, m, s): &(Option<i64>, Option<i64>, Option<i64>)| {
+                h.is_some() || m.is_some() || s.is_some()
+            },
+        ),
+    ))
+    .parse(input)?;
+    let (hours, minutes, seconds) = time.unwrap_or_default();
+    let delta = match (weeks, days, time) {
+        (None, None, None) => None,
+        _ => [
+            TimeDelta::try_weeks(weeks.unwrap_or(0)),
+            TimeDelta::try_days(days.unwrap_or(0)),
+            TimeDelta::try_hours(hours.unwrap_or(0)),
+            TimeDelta::try_minutes(minutes.unwrap_or(0)),
+            TimeDelta::try_seconds(seconds.unwrap_or(0)),
+        ]
+        .into_iter()
+        .try_fold(TimeDelta::zero(), |sum, delta| sum.checked_add(&delta?)),
+    };
+    let Some(delta) = delta else {
+        return Err(nom::Err::Error(nom::error::Error::new(
+            input,
+            nom::error::ErrorKind::Verify,
+        )));
+    };
+    let delta = match sign {
+        Some('-') => -delta,
+        _ => delta,
+    };
+    Ok((input, delta))
+}
+
+fn number(input: &str) -> IResult<&str, i64> {
+    digit1.map_res(str::parse).parse(input)
+}
+
+/// Format as ISO 8601 duration, e.g. `-PT15M` or `P1DT12H`.
+pub fn format_duration(delta: TimeDelta) -> String {
+    let sign = if delta < TimeDelta::zero() { "-" } else { "" };
+    let seconds = delta.num_seconds().abs();
+    let (days, rest) = (seconds / 86400, seconds % 86400);
+    let (hours, minutes, seconds) = (rest / 3600, rest % 3600 / 60, rest % 60);
+
+    let mut formatted = format!("{sign}P");
+    if days > 0 {
+        formatted.push_str(&format!("{days}D"));
+    }
+    if rest > 0 || days == 0 {
+        formatted.push('T');
+        if hours > 0 {
+            formatted.push_str(&format!("{hours}H"));
+        }
+        if minutes > 0 {
+            formatted.push_str(&format!("{minutes}M"));
+        }
+        if seconds > 0 || rest == 0 {
+            formatted.push_str(&format!("{seconds}S"));
+        }
+    }
+    formatted
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    macro_rules! case {
+        ($input:expr, $delta:expr) => {
+            assert_eq!(duration($input), Ok(("", $delta)));
+        };
+    }
+
+    #[test]
+    fn parse() {
+        case!("PT15M", TimeDelta::minutes(15));
+        case!("-PT15M", TimeDelta::minutes(-15));
+        case!("+P1D", TimeDelta::days(1));
+        case!("P2W", TimeDelta::weeks(2));
+        case!(
+            "P1DT2H3M4S",
+            TimeDelta::days(1)
+                + TimeDelta::hours(2)
+                + TimeDelta::minutes(3)
+                + TimeDelta::seconds(4)
+        );
+        case!("PT0S", TimeDelta::zero());
+    }
+
+    #[test]
+    fn parse_err() {
+        assert!(duration("P").is_err());
+        assert!(duration("PT").is_err());
+        assert!(duration("P1Y").is_err());
+        assert!(duration("15M").is_err());
+        // out of range
+        assert!(duration("-P99999999999999W").is_err());
+        assert!(duration("P999999999999D").is_err());
+        // each in range, but not the sum
+        assert!(duration("P15000000000DT9000000000000000S").is_err());
+    }
+
+    #[test]
+    fn format() {
+        assert_eq!(format_duration(TimeDelta::minutes(-15)), "-PT15M");
+        assert_eq!(format_duration(TimeDelta::days(1)), "P1D");
+        assert_eq!(
+            format_duration(TimeDelta::days(1) + TimeDelta::hours(12)),
+            "P1DT12H"
+        );
+        assert_eq!(format_duration(TimeDelta::zero()), "PT0S");
+    }
+}
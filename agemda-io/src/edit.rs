@@ -0,0 +1,288 @@
+use std::{
+    collections::BTreeMap,
+    fs,
+    ops::Range,
+    path::{Path, PathBuf},
+};
+
+use agemda_core::{Clock, Status, Todo};
+use anyhow::{Context, bail};
+use chrono::{DateTime, Local};
+use pulldown_cmark::{Event, Tag};
+
+use crate::{
+    clock::format_period,
+    link::link_with,
+    parse::{ParseOptions, markdown_parser, mask_task_markers},
+};
+
+/// A replacement of a byte range of a file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Edit {
+    pub path: PathBuf,
+    pub range: Range<usize>,
+    /// The text of range when the edit was made, which must be unchanged to apply.
+    pub expected: String,
+    pub text: String,
+}
+
+/// The edit adding `key=value` to the own link of todo,
+/// or a new link after its summary if it has no link.
+///
+/// `text` is the content of the file of todo.
+pub fn add_key(text: &str, todo: &Todo, key: &str, value: &str) -> anyhow::Result<Edit> {
+    let metadata = &todo.metadata;
+    let (range, replacement) = match &metadata.link {
+        // at the end of the destination, before any title or closing `>` or `)`
+        Some(link) => {
+            let Some(range) = destination(text, link) else {
+                bail!(
+                    "fail to locate the link destination at line {}",
+                    metadata.line
+                );
+            };
+            let dest = &text[range.clone()];
+            let separator = match dest == "agmd:" {
+                true => "",
+                false => ";",
+            };
+            (range, format!("{dest}{separator}{key}={value}"))
+        }
+        None => (
+            metadata.summary_end..metadata.summary_end,
+            format!(" <agmd:{key}={value}>"),
+        ),
+    };
+    Ok(Edit {
+        path: metadata.path.clone(),
+        expected: text[range.clone()].to_string(),
+        range,
+        text: replacement,
+    })
+}
+
+/// The byte range of the destination of link in text, e.g. `agmd:2025-03-09`.
+fn destination(text: &str, link: &Range<usize>) -> Option<Range<usize>> {
+    let source = text.get(link.clone())?;
+    let dest = markdown_parser(source).find_map(|event| match event {
+        Event::Start(Tag::Link { dest_url, .. }) => Some(dest_url),
+        _ => None,
+    })?;
+    // the destination comes after the text of link, and may be escaped in source
+    let start = link.start + source.rfind(&*dest)?;
+    Some(start..start + dest.len())
+}
+
+/// The edit replacing the marker of todo with the one of status.
+pub fn set_status(text: &str, todo: &Todo, status: Status) -> Edit {
+    let marker = &todo.metadata.marker;
+    let range = marker.start + 1..marker.start + 2;
+    Edit {
+        path: todo.metadata.path.clone(),
+        expected: text.get(range.clone()).unwrap_or_default().to_string(),
+        range,
+        text: status.marker().to_string(),
+    }
+}
+
+/// The edit inserting a running clock from `now`, as an item after the own content of the task.
+pub fn start_clock(text: &str, todo: &Todo, now: DateTime<Local>) -> Edit {
+    let metadata = &todo.metadata;
+    let offset = text[metadata.content_end..]
+        .find('\n')
+        .map_or(text.len(), |i| metadata.content_end + i);
+    // align with the marker, where the content of item starts
+    let line_start = text[..metadata.marker.start]
+        .rfind('\n')
+        .map_or(0, |i| i + 1);
+    let indent = " ".repeat(metadata.marker.start - line_start);
+    Edit {
+        path: metadata.path.clone(),
+        range: offset..offset,
+        expected: String::new(),
+        text: format!("\n{indent}- CLOCK: {}", format_period(now, None)),
+    }
+}
+
+/// The edit ending the clock of todo at `now`.
+pub fn stop_clock(text: &str, todo: &Todo, clock: &Clock, now: DateTime<Local>) -> Edit {
+    Edit {
+        path: todo.metadata.path.clone(),
+        range: clock.range.clone(),
+        expected: text
+            .get(clock.range.clone())
+            .unwrap_or_default()
+            .to_string(),
+        text: format_period(clock.start, Some(now)),
+    }
+}
+
+/// The edits rewriting every agmd link of the file in canonical form,
+/// e.g. `<agmd:2025-03-09;due=2025-03-12>` to `<agmd:2025-03-09;due=12>`.
+///
+/// Malformed links are kept as is.
+pub fn canonical_links(path: impl AsRef<Path>, text: &str, options: &ParseOptions) -> Vec<Edit> {
+    let masked = mask_task_markers(text);
+    markdown_parser(&masked)
+        .into_offset_iter()
+        .filter_map(|(event, range)| {
+            let Event::Start(Tag::Link { dest_url, .. }) = event else {
+                return None;
+            };
+            let agmd = dest_url.strip_prefix("agmd:")?;
+            let (_, link) = link_with(agmd, options.date_order).ok()?;
+            let canonical = link.canonical().to_string();
+            // the destination comes after the text of link, and may be escaped in source
+            let start = range.start + text[range].rfind(&format!("agmd:{agmd}"))? + "agmd:".len();
+            (canonical != agmd).then(|| Edit {
+                path: path.as_ref().to_path_buf(),
+                range: start..start + agmd.len(),
+                expected: agmd.to_string(),
+                text: canonical,
+            })
+        })
+        .collect()
+}
+
+/// Apply edits to text, which must not overlap,
+/// and fail if text has changed since the edits were made.
+pub fn apply(text: &str, edits: &[&Edit]) -> anyhow::Result<String> {
+    let mut edits = edits.to_vec();
+    edits.sort_by_key(|edit| (edit.range.start, edit.range.end));
+    let mut result = String::with_capacity(text.len());
+    let mut last = 0;
+    for edit in edits {
+        if edit.range.start < last {
+            bail!("overlapping edits at {}", edit.range.start);
+        }
+        match text.get(edit.range.clone()) {
+            Some(current) if current == edit.expected => {}
+            Some(_) => bail!("text changed at {} since the edit", edit.range.start),
+            None => bail!("edit out of text at {}", edit.range.start),
+        }
+        result.push_str(&text[last..edit.range.start]);
+        result.push_str(&edit.text);
+        last = edit.range.end;
+    }
+    result.push_str(&text[last..]);
+    Ok(result)
+}
+
+/// Apply edits to their files.
+pub fn apply_to_files(edits: &[Edit]) -> anyhow::Result<()> {
+    let mut by_path: BTreeMap<_, Vec<_>> = BTreeMap::new();
+    for edit in edits {
+        by_path.entry(&edit.path).or_default().push(edit);
+    }
+    for (path, edits) in by_path {
+        let text = fs::read_to_string(path)
+            .with_context(|| format!("fail to read file {}", path.display()))?;
+        let text = apply(&text, &edits)?;
+        fs::write(path, text).with_context(|| format!("fail to write file {}", path.display()))?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use crate::parse::{ParseOptions, parse_text};
+
+    use super::*;
+
+    fn edited(text: &str, edit: impl Fn(&Todo) -> Edit) -> String {
+        let mut todos = vec![];
+        parse_text(&mut todos, "test.md", text, &ParseOptions::default());
+        let edits: Vec<_> = todos.iter().map(edit).collect();
+        apply(text, &edits.iter().collect::<Vec<_>>()).unwrap()
+    }
+
+    #[test]
+    fn add_key_to_link() {
+        let text = "- [ ] a <agmd:>\n- [ ] b <agmd:2025-03-09>\n  - [ ] *c*\n";
+        assert_eq!(
+            edited(text, |todo| add_key(text, todo, "uid", "x").unwrap()),
+            "- [ ] a <agmd:uid=x>\n- [ ] b <agmd:2025-03-09;uid=x>\n  - [ ] *c* <agmd:uid=x>\n"
+        );
+        let text = "- [ ] a [x](agmd:2025-03-09 \"t\")\n- [ ] b [y](<agmd:due=12>)\n";
+        assert_eq!(
+            edited(text, |todo| add_key(text, todo, "uid", "x").unwrap()),
+            "- [ ] a [x](agmd:2025-03-09;uid=x \"t\")\n- [ ] b [y](<agmd:due=12;uid=x>)\n"
+        );
+    }
+
+    #[test]
+    fn stale_edit() {
+        let text = "- [ ] a <agmd:2025-03-09>\n";
+        let mut todos = vec![];
+        parse_text(&mut todos, "test.md", text, &ParseOptions::default());
+        let edit = add_key(text, &todos[0], "uid", "x").unwrap();
+        assert!(apply("- [ ] a <agmd:2025-03-10>\n", &[&edit]).is_err());
+        assert!(apply("- [ ] a\n", &[&edit]).is_err());
+        let edit = set_status(text, &todos[0], Status::Completed);
+        assert!(apply("- [x] a <agmd:2025-03-09>\n", &[&edit]).is_err());
+    }
+
+    #[test]
+    fn clock() {
+        let now = crate::convert::of_second(2025, 3, 9, 10, 0, 0).unwrap();
+        let text = "- [ ] a <agmd:>\n  - [ ] b\n    more <agmd:>\n";
+        let started = edited(text, |todo| start_clock(text, todo, now));
+        assert_eq!(
+            started,
+            "- [ ] a <agmd:>\n  - CLOCK: 2025-03-09T10:00--\n  - [ ] b\n    more <agmd:>\n    - CLOCK: 2025-03-09T10:00--\n"
+        );
+
+        let later = crate::convert::of_second(2025, 3, 9, 11, 30, 0).unwrap();
+        let stopped = edited(&started, |todo| {
+            stop_clock(&started, todo, todo.running_clock().unwrap(), later)
+        });
+        assert_eq!(
+            stopped,
+            "- [ ] a <agmd:>\n  - CLOCK: 2025-03-09T10:00--11:30\n  - [ ] b\n    more <agmd:>\n    - CLOCK: 2025-03-09T10:00--11:30\n"
+        );
+    }
+
+    #[test]
+    fn clock_after_description() {
+        let now = crate::convert::of_second(2025, 3, 9, 10, 0, 0).unwrap();
+        let text = "- [ ] a <agmd:2025-03-09>\n\n  Acceptance: it works\n- [ ] b <agmd:>\n";
+        let started = edited(text, |todo| start_clock(text, todo, now));
+        assert_eq!(
+            started,
+            "- [ ] a <agmd:2025-03-09>\n\n  Acceptance: it works\n  - CLOCK: 2025-03-09T10:00--\n- [ ] b <agmd:>\n  - CLOCK: 2025-03-09T10:00--\n"
+        );
+
+        let mut todos = vec![];
+        parse_text(&mut todos, "test.md", &started, &ParseOptions::default());
+        let description = todos[0].description.as_ref().unwrap();
+        assert_eq!(description.markdown, "Acceptance: it works");
+        assert_eq!(todos[0].clocks.len(), 1);
+    }
+
+    #[test]
+    fn canonical_link() {
+        let text = "---\nagmd: 2025-03-09;due=2025-03-09\n---\n\
+            # Week <agmd:2025-03-10;start=2025-03-10>\n\
+            - [-] a <agmd:2025-03-09..2025-03-12;priority=high>\n\
+            - [ ] b [due](agmd:due=20250312) <agmd:bad=;due=soon>\n\
+            - [ ] c <agmd:2025-03-09;note=later;due=2025-03-12>\n";
+        let edits = canonical_links("test.md", text, &ParseOptions::default());
+        assert_eq!(
+            apply(text, &edits.iter().collect::<Vec<_>>()).unwrap(),
+            "---\nagmd: 2025-03-09;due=2025-03-09\n---\n\
+            # Week <agmd:2025-03-10>\n\
+            - [-] a <agmd:2025-03-09..12;priority=1>\n\
+            - [ ] b [due](agmd:due=2025-03-12) <agmd:bad=;due=soon>\n\
+            - [ ] c <agmd:2025-03-09..12;note=later>\n"
+        );
+    }
+
+    #[test]
+    fn set_marker() {
+        let text = "- [ ] a <agmd:>\n- [/] b <agmd:>\n";
+        assert_eq!(
+            edited(text, |todo| set_status(text, todo, Status::Completed)),
+            "- [x] a <agmd:>\n- [x] b <agmd:>\n"
+        );
+    }
+}
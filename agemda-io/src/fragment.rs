@@ -1,15 +1,43 @@
+use std::{fmt, str::FromStr};
+
+use chrono::{Datelike, FixedOffset, NaiveDate, Weekday};
 use nom::{
     IResult, Parser,
     branch::alt,
     bytes::complete::{tag, take},
-    combinator::{eof, not, opt, peek},
+    character::complete::{digit1, one_of, satisfy},
+    combinator::{not, opt, peek},
     sequence::{preceded, terminated},
 };
 
+#[derive(Clone, Debug, PartialEq)]
+pub struct SecondRest {
+    pub second: u32,
+    /// The fraction of second, e.g. `250_000_000` of `.250`.
+    pub nanosecond: Option<u32>,
+}
+
+impl SecondRest {
+    pub fn from_s(second: u32) -> Self {
+        Self {
+            second,
+            nanosecond: None,
+        }
+    }
+
+    pub fn second(&self) -> u32 {
+        self.second
+    }
+
+    pub fn nanosecond(&self) -> Option<u32> {
+        self.nanosecond
+    }
+}
+
 #[derive(Clone, Debug, PartialEq)]
 pub struct MinuteRest {
     pub minute: u32,
-    pub rest: Option<u32>,
+    pub rest: Option<SecondRest>,
 }
 
 impl MinuteRest {
@@ -20,7 +48,7 @@ impl MinuteRest {
     pub fn from_ms(minute: u32, second: u32) -> Self {
         Self {
             minute,
-            rest: Some(second),
+            rest: Some(SecondRest::from_s(second)),
         }
     }
 
@@ -29,7 +57,11 @@ impl MinuteRest {
     }
 
     pub fn second(&self) -> Option<u32> {
-        self.rest
+        self.rest.as_ref().map(|r| r.second())
+    }
+
+    pub fn nanosecond(&self) -> Option<u32> {
+        self.rest.as_ref().and_then(|r| r.nanosecond())
     }
 }
 
@@ -37,17 +69,24 @@ impl MinuteRest {
 pub struct HourRest {
     pub hour: u32,
     pub rest: Option<MinuteRest>,
+    /// The offset from UTC, e.g. `Z` or `+05:30`, otherwise local time.
+    pub offset: Option<FixedOffset>,
 }
 
 impl HourRest {
     pub fn from_h(hour: u32) -> Self {
-        Self { hour, rest: None }
+        Self {
+            hour,
+            rest: None,
+            offset: None,
+        }
     }
 
     pub fn from_hm(hour: u32, minute: u32) -> Self {
         Self {
             hour,
             rest: Some(MinuteRest::from_m(minute)),
+            offset: None,
         }
     }
 
@@ -55,6 +94,7 @@ impl HourRest {
         Self {
             hour,
             rest: Some(MinuteRest::from_ms(minute, second)),
+            offset: None,
         }
     }
 
@@ -69,6 +109,14 @@ impl HourRest {
     pub fn second(&self) -> Option<u32> {
         self.rest.as_ref().and_then(|r| r.second())
     }
+
+    pub fn nanosecond(&self) -> Option<u32> {
+        self.rest.as_ref().and_then(|r| r.nanosecond())
+    }
+
+    pub fn offset(&self) -> Option<FixedOffset> {
+        self.offset
+    }
 }
 
 #[derive(Clone, Debug, PartialEq)]
@@ -118,6 +166,14 @@ impl DayRest {
     pub fn second(&self) -> Option<u32> {
         self.rest.as_ref().and_then(|r| r.second())
     }
+
+    pub fn nanosecond(&self) -> Option<u32> {
+        self.rest.as_ref().and_then(|r| r.nanosecond())
+    }
+
+    pub fn offset(&self) -> Option<FixedOffset> {
+        self.rest.as_ref().and_then(|r| r.offset())
+    }
 }
 
 // TODO: month distinguish
@@ -179,6 +235,14 @@ impl MonthRest {
     pub fn second(&self) -> Option<u32> {
         self.rest.as_ref().and_then(|r| r.second())
     }
+
+    pub fn nanosecond(&self) -> Option<u32> {
+        self.rest.as_ref().and_then(|r| r.nanosecond())
+    }
+
+    pub fn offset(&self) -> Option<FixedOffset> {
+        self.rest.as_ref().and_then(|r| r.offset())
+    }
 }
 
 #[derive(Clone, Debug, PartialEq)]
@@ -257,6 +321,106 @@ impl YearRest {
     pub fn second(&self) -> Option<u32> {
         self.rest.as_ref().and_then(|r| r.second())
     }
+
+    pub fn nanosecond(&self) -> Option<u32> {
+        self.rest.as_ref().and_then(|r| r.nanosecond())
+    }
+
+    pub fn offset(&self) -> Option<FixedOffset> {
+        self.rest.as_ref().and_then(|r| r.offset())
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum OffsetUnit {
+    Hour,
+    Day,
+    Week,
+    Month,
+    Year,
+}
+
+/// A signed offset such as `+3d` or `-1m`, measured from another date time.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Offset {
+    pub amount: i32,
+    pub unit: OffsetUnit,
+}
+
+/// An ISO week `2025-W11`, or a day of it `2025-W11-3` with Monday as 1.
+#[derive(Clone, Debug, PartialEq)]
+pub struct WeekDate {
+    pub year: i32,
+    pub week: u32,
+    pub weekday: Option<u32>,
+}
+
+impl WeekDate {
+    /// The date of weekday, `None` for the whole week or an invalid date.
+    pub fn date(&self) -> Option<NaiveDate> {
+        let weekday = Weekday::try_from(u8::try_from(self.weekday?.checked_sub(1)?).ok()?).ok()?;
+        NaiveDate::from_isoywd_opt(self.year, self.week, weekday)
+    }
+}
+
+/// An ordinal date, e.g. `2025-068` for the 68th day of 2025.
+#[derive(Clone, Debug, PartialEq)]
+pub struct OrdinalDate {
+    pub year: i32,
+    pub ordinal: u32,
+}
+
+impl OrdinalDate {
+    pub fn date(&self) -> Option<NaiveDate> {
+        NaiveDate::from_yo_opt(self.year, self.ordinal)
+    }
+}
+
+/// A quarter of year, e.g. `2025-Q2` from April to June.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Quarter {
+    pub year: i32,
+    pub quarter: u32,
+}
+
+/// The order of day and month in a locale date like `09/03/2025`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DateOrder {
+    /// `DD/MM/YYYY`.
+    DayFirst,
+    /// `MM/DD/YYYY`.
+    MonthFirst,
+}
+
+impl FromStr for DateOrder {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "day-first" => Ok(DateOrder::DayFirst),
+            "month-first" => Ok(DateOrder::MonthFirst),
+            _ => Err(format!(
+                "unknown date order {s}, expect day-first or month-first"
+            )),
+        }
+    }
+}
+
+/// A named day resolved against the base date or today.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Anchor {
+    /// The weekday on or after the date, e.g. `fri`.
+    Weekday(Weekday),
+    /// The weekday of the week after the date, e.g. `next-mon`.
+    NextWeekday(Weekday),
+    /// The last day of the week, `eow`.
+    EndOfWeek,
+    /// The last day of the month, `eom`.
+    EndOfMonth,
+    /// The last day of the quarter, `eoq`.
+    EndOfQuarter,
+    /// The last day of the year, `eoy`.
+    EndOfYear,
 }
 
 #[derive(Clone, Debug, PartialEq)]
@@ -266,6 +430,11 @@ pub enum DateTimeFragment {
     MonthRest(MonthRest),
     DayRest(DayRest),
     HourRest(Option<HourRest>),
+    Offset(Offset),
+    Anchor(Anchor),
+    Week(WeekDate),
+    Ordinal(OrdinalDate),
+    Quarter(Quarter),
 }
 
 impl DateTimeFragment {
@@ -354,9 +523,59 @@ impl DateTimeFragment {
         Self::HourRest(Some(HourRest::from_hms(hour, minute, second)))
     }
 
+    pub fn from_offset(amount: i32, unit: OffsetUnit) -> Self {
+        Self::Offset(Offset { amount, unit })
+    }
+
+    pub fn from_anchor(anchor: Anchor) -> Self {
+        Self::Anchor(anchor)
+    }
+
+    pub fn from_yw(year: i32, week: u32) -> Self {
+        Self::Week(WeekDate {
+            year,
+            week,
+            weekday: None,
+        })
+    }
+
+    pub fn from_ywd(year: i32, week: u32, weekday: u32) -> Self {
+        Self::Week(WeekDate {
+            year,
+            week,
+            weekday: Some(weekday),
+        })
+    }
+
+    pub fn from_yo(year: i32, ordinal: u32) -> Self {
+        Self::Ordinal(OrdinalDate { year, ordinal })
+    }
+
+    pub fn from_yq(year: i32, quarter: u32) -> Self {
+        Self::Quarter(Quarter { year, quarter })
+    }
+
+    /// The calendar date of a week day or ordinal date.
+    fn date(&self) -> Option<NaiveDate> {
+        match self {
+            DateTimeFragment::Week(week) => week.date(),
+            DateTimeFragment::Ordinal(ordinal) => ordinal.date(),
+            _ => None,
+        }
+    }
+
     pub fn year(&self) -> Option<i32> {
         match self {
             DateTimeFragment::YearRest(year_rest) => Some(year_rest.year()),
+            DateTimeFragment::Week(WeekDate {
+                year,
+                weekday: None,
+                ..
+            })
+            | DateTimeFragment::Quarter(Quarter { year, .. }) => Some(*year),
+            DateTimeFragment::Week(_) | DateTimeFragment::Ordinal(_) => {
+                self.date().map(|date| date.year())
+            }
             _ => None,
         }
     }
@@ -365,6 +584,9 @@ impl DateTimeFragment {
         match self {
             DateTimeFragment::YearRest(year_rest) => year_rest.month(),
             DateTimeFragment::MonthRest(month_rest) => Some(month_rest.month()),
+            DateTimeFragment::Week(_) | DateTimeFragment::Ordinal(_) => {
+                self.date().map(|date| date.month())
+            }
             _ => None,
         }
     }
@@ -374,6 +596,9 @@ impl DateTimeFragment {
             DateTimeFragment::YearRest(year_rest) => year_rest.day(),
             DateTimeFragment::MonthRest(month_rest) => month_rest.day(),
             DateTimeFragment::DayRest(day_rest) => Some(day_rest.day()),
+            DateTimeFragment::Week(_) | DateTimeFragment::Ordinal(_) => {
+                self.date().map(|date| date.day())
+            }
             _ => None,
         }
     }
@@ -407,12 +632,180 @@ impl DateTimeFragment {
             _ => None,
         }
     }
+
+    pub fn nanosecond(&self) -> Option<u32> {
+        match self {
+            DateTimeFragment::YearRest(year_rest) => year_rest.nanosecond(),
+            DateTimeFragment::MonthRest(month_rest) => month_rest.nanosecond(),
+            DateTimeFragment::DayRest(day_rest) => day_rest.nanosecond(),
+            DateTimeFragment::HourRest(hour_rest) => {
+                hour_rest.as_ref().and_then(|r| r.nanosecond())
+            }
+            _ => None,
+        }
+    }
+
+    pub fn offset(&self) -> Option<FixedOffset> {
+        match self {
+            DateTimeFragment::YearRest(year_rest) => year_rest.offset(),
+            DateTimeFragment::MonthRest(month_rest) => month_rest.offset(),
+            DateTimeFragment::DayRest(day_rest) => day_rest.offset(),
+            DateTimeFragment::HourRest(hour_rest) => hour_rest.as_ref().and_then(|r| r.offset()),
+            _ => None,
+        }
+    }
+}
+
+/// Canonical text of the fragment in ISO 8601 extended format, parsed back as is,
+/// e.g. `2025-03-09T10:30`, `10-` of a month or `12T` of a day.
+impl fmt::Display for DateTimeFragment {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DateTimeFragment::YearRest(year_rest) => write!(f, "{year_rest}"),
+            DateTimeFragment::MonthOrDay(month_or_day) => write!(f, "{month_or_day:02}"),
+            DateTimeFragment::MonthRest(month_rest) => match month_rest.rest {
+                Some(_) => write!(f, "{month_rest}"),
+                None => write!(f, "{month_rest}-"),
+            },
+            DateTimeFragment::DayRest(day_rest) => match day_rest.rest {
+                Some(_) => write!(f, "{day_rest}"),
+                None => write!(f, "{day_rest}T"),
+            },
+            DateTimeFragment::HourRest(Some(hour_rest)) => write!(f, "{hour_rest}"),
+            DateTimeFragment::HourRest(None) => write!(f, "T"),
+            DateTimeFragment::Offset(offset) => write!(f, "{offset}"),
+            DateTimeFragment::Anchor(anchor) => write!(f, "{anchor}"),
+            DateTimeFragment::Week(week) => write!(f, "{week}"),
+            DateTimeFragment::Ordinal(ordinal) => {
+                write!(f, "{:04}-{:03}", ordinal.year, ordinal.ordinal)
+            }
+            DateTimeFragment::Quarter(quarter) => {
+                write!(f, "{:04}-Q{}", quarter.year, quarter.quarter)
+            }
+        }
+    }
+}
+
+impl fmt::Display for YearRest {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:04}", self.year)?;
+        match &self.rest {
+            Some(rest) => write!(f, "-{rest}"),
+            None => Ok(()),
+        }
+    }
+}
+
+impl fmt::Display for MonthRest {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:02}", self.month)?;
+        match &self.rest {
+            Some(rest) => write!(f, "-{rest}"),
+            None => Ok(()),
+        }
+    }
+}
+
+impl fmt::Display for DayRest {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:02}", self.day)?;
+        match &self.rest {
+            Some(rest) => write!(f, "{rest}"),
+            None => Ok(()),
+        }
+    }
+}
+
+impl fmt::Display for HourRest {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "T{:02}", self.hour)?;
+        if let Some(rest) = &self.rest {
+            write!(f, ":{rest}")?;
+        }
+        match self.offset.map(|offset| offset.local_minus_utc()) {
+            None => Ok(()),
+            Some(0) => write!(f, "Z"),
+            Some(seconds) => {
+                let sign = if seconds < 0 { '-' } else { '+' };
+                let minutes = seconds.unsigned_abs() / 60;
+                write!(f, "{sign}{:02}:{:02}", minutes / 60, minutes % 60)
+            }
+        }
+    }
+}
+
+impl fmt::Display for MinuteRest {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:02}", self.minute)?;
+        match &self.rest {
+            Some(rest) => write!(f, ":{rest}"),
+            None => Ok(()),
+        }
+    }
+}
+
+impl fmt::Display for SecondRest {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:02}", self.second)?;
+        match self.nanosecond {
+            Some(nanosecond) => {
+                let fraction = format!("{nanosecond:09}");
+                let fraction = fraction.trim_end_matches('0');
+                write!(f, ".{}", if fraction.is_empty() { "0" } else { fraction })
+            }
+            None => Ok(()),
+        }
+    }
+}
+
+impl fmt::Display for WeekDate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:04}-W{:02}", self.year, self.week)?;
+        match self.weekday {
+            Some(weekday) => write!(f, "-{weekday}"),
+            None => Ok(()),
+        }
+    }
+}
+
+impl fmt::Display for Offset {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let sign = if self.amount < 0 { '-' } else { '+' };
+        let unit = match self.unit {
+            OffsetUnit::Hour => 'h',
+            OffsetUnit::Day => 'd',
+            OffsetUnit::Week => 'w',
+            OffsetUnit::Month => 'm',
+            OffsetUnit::Year => 'y',
+        };
+        write!(f, "{sign}{}{unit}", self.amount.unsigned_abs())
+    }
+}
+
+impl fmt::Display for Anchor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let weekday = |weekday: &Weekday| weekday.to_string().to_lowercase();
+        match self {
+            Anchor::Weekday(day) => write!(f, "{}", weekday(day)),
+            Anchor::NextWeekday(day) => write!(f, "next-{}", weekday(day)),
+            Anchor::EndOfWeek => write!(f, "eow"),
+            Anchor::EndOfMonth => write!(f, "eom"),
+            Anchor::EndOfQuarter => write!(f, "eoq"),
+            Anchor::EndOfYear => write!(f, "eoy"),
+        }
+    }
 }
 
 pub fn date_time_fragment(input: &str) -> IResult<&str, DateTimeFragment> {
     use DateTimeFragment::*;
 
     alt((
+        offset.map(Offset),
+        anchor.map(Anchor),
+        basic_date_time.map(YearRest),
+        week_date.map(Week),
+        ordinal_date.map(Ordinal),
+        quarter.map(Quarter),
         year_rest.map(YearRest),
         month_or_day.map(MonthOrDay),
         month_rest.map(MonthRest),
@@ -422,6 +815,81 @@ pub fn date_time_fragment(input: &str) -> IResult<&str, DateTimeFragment> {
     .parse(input)
 }
 
+/// Parse the fragment, also accepting a locale date like `09/03/2025` of the order if given.
+pub fn date_time_fragment_with(
+    input: &str,
+    order: Option<DateOrder>,
+) -> IResult<&str, DateTimeFragment> {
+    match order {
+        Some(order) => alt((
+            |input| {
+                locale_date(input, order)
+                    .map(|(input, date)| (input, DateTimeFragment::YearRest(date)))
+            },
+            date_time_fragment,
+        ))
+        .parse(input),
+        None => date_time_fragment(input),
+    }
+}
+
+/// A locale date with optional time, e.g. `09/03/2025T10:30` of day first.
+pub fn locale_date(input: &str, order: DateOrder) -> IResult<&str, YearRest> {
+    let (input, (first, _, second, _, year)) =
+        (two_digits, tag("/"), two_digits, tag("/"), four_digits).parse(input)?;
+    let (day, month) = match order {
+        DateOrder::DayFirst => (first, second),
+        DateOrder::MonthFirst => (second, first),
+    };
+    let (input, time) = opt(hour_rest).parse(input)?;
+    Ok((
+        input,
+        YearRest {
+            year,
+            rest: Some(MonthRest {
+                month,
+                rest: Some(DayRest {
+                    day,
+                    rest: time.flatten(),
+                }),
+            }),
+        },
+    ))
+}
+
+/// The basic format of ISO 8601, e.g. `20250309` or `20250309T103000Z`.
+pub fn basic_date_time(input: &str) -> IResult<&str, YearRest> {
+    let (input, (year, month, day)) = (four_digits, two_digits, two_digits).parse(input)?;
+    let (input, time) = opt(preceded(
+        cap_t,
+        (
+            two_digits,
+            opt((two_digits, opt(two_digits))),
+            opt(time_offset),
+        ),
+    ))
+    .parse(input)?;
+    not(peek(digit1)).parse(input)?;
+    let time = time.map(|(hour, rest, offset)| HourRest {
+        hour,
+        rest: rest.map(|(minute, second)| MinuteRest {
+            minute,
+            rest: second.map(SecondRest::from_s),
+        }),
+        offset,
+    });
+    Ok((
+        input,
+        YearRest {
+            year,
+            rest: Some(MonthRest {
+                month,
+                rest: Some(DayRest { day, rest: time }),
+            }),
+        },
+    ))
+}
+
 pub fn year_rest(input: &str) -> IResult<&str, YearRest> {
     let (input, year) = four_digits(input)?;
     let (input, rest) = opt(preceded(hyphen, opt(month_rest))).parse(input)?;
@@ -435,8 +903,7 @@ pub fn year_rest(input: &str) -> IResult<&str, YearRest> {
 }
 
 pub fn month_or_day(input: &str) -> IResult<&str, u32> {
-    // XXX: should be neither T or -
-    terminated(two_digits, eof).parse(input)
+    terminated(two_digits, not(peek(one_of("-T0123456789")))).parse(input)
 }
 
 pub fn month_rest(input: &str) -> IResult<&str, MonthRest> {
@@ -466,19 +933,25 @@ pub fn day_rest(input: &str) -> IResult<&str, DayRest> {
 
 pub fn hour_rest(input: &str) -> IResult<&str, Option<HourRest>> {
     let (input, _) = cap_t(input)?;
-    let (input, res) = opt((two_digits, opt(preceded(colon, opt(minute_rest))))).parse(input)?;
+    let (input, res) = opt((
+        two_digits,
+        opt(preceded(colon, opt(minute_rest))),
+        opt(time_offset),
+    ))
+    .parse(input)?;
     Ok((
         input,
-        res.map(|(hour, rest)| HourRest {
+        res.map(|(hour, rest, offset)| HourRest {
             hour,
             rest: rest.flatten(),
+            offset,
         }),
     ))
 }
 
 pub fn minute_rest(input: &str) -> IResult<&str, MinuteRest> {
     let (input, minute) = two_digits(input)?;
-    let (input, rest) = opt(preceded(colon, opt(two_digits))).parse(input)?;
+    let (input, rest) = opt(preceded(colon, opt(second_rest))).parse(input)?;
     Ok((
         input,
         MinuteRest {
@@ -488,14 +961,131 @@ pub fn minute_rest(input: &str) -> IResult<&str, MinuteRest> {
     ))
 }
 
+pub fn second_rest(input: &str) -> IResult<&str, SecondRest> {
+    let (input, second) = two_digits(input)?;
+    let (input, nanosecond) = opt(preceded(tag("."), fraction)).parse(input)?;
+    Ok((input, SecondRest { second, nanosecond }))
+}
+
+/// Digits of the fraction of second as nanoseconds, up to 9 digits.
+pub fn fraction(input: &str) -> IResult<&str, u32> {
+    digit1
+        .map_opt(|digits: &str| {
+            if digits.len() > 9 {
+                return None;
+            }
+            let nanosecond: u32 = digits.parse().ok()?;
+            Some(nanosecond * 10u32.pow(9 - digits.len() as u32))
+        })
+        .parse(input)
+}
+
+/// The offset from UTC of RFC 3339, `Z` or `+HH:MM`.
+pub fn time_offset(input: &str) -> IResult<&str, FixedOffset> {
+    alt((
+        one_of("Zz").map_opt(|_| FixedOffset::east_opt(0)),
+        (one_of("+-"), two_digits, colon, two_digits).map_opt(|(sign, hour, _, minute)| {
+            if minute >= 60 {
+                return None;
+            }
+            let seconds = i32::try_from(hour * 3600 + minute * 60).ok()?;
+            match sign {
+                '+' => FixedOffset::east_opt(seconds),
+                _ => FixedOffset::west_opt(seconds),
+            }
+        }),
+    ))
+    .parse(input)
+}
+
+pub fn week_date(input: &str) -> IResult<&str, WeekDate> {
+    let (input, year) = terminated(four_digits, tag("-W")).parse(input)?;
+    let (input, week) = two_digits(input)?;
+    let (input, weekday) = opt(preceded(hyphen, one_digit)).parse(input)?;
+    not(peek(digit1)).parse(input)?;
+    Ok((
+        input,
+        WeekDate {
+            year,
+            week,
+            weekday,
+        },
+    ))
+}
+
+pub fn ordinal_date(input: &str) -> IResult<&str, OrdinalDate> {
+    let (input, year) = terminated(four_digits, hyphen).parse(input)?;
+    let (input, ordinal) = terminated(three_digits, not(peek(digit1))).parse(input)?;
+    Ok((input, OrdinalDate { year, ordinal }))
+}
+
+pub fn quarter(input: &str) -> IResult<&str, Quarter> {
+    let (input, year) = terminated(four_digits, tag("-Q")).parse(input)?;
+    let (input, quarter) = terminated(one_digit, not(peek(digit1))).parse(input)?;
+    Ok((input, Quarter { year, quarter }))
+}
+
+pub fn offset(input: &str) -> IResult<&str, Offset> {
+    let (input, sign) = one_of("+-").parse(input)?;
+    let (input, amount) = digit1.map_res(|x: &str| x.parse::<i32>()).parse(input)?;
+    let (input, unit) = one_of("hdwmy").parse(input)?;
+    let unit = match unit {
+        'h' => OffsetUnit::Hour,
+        'd' => OffsetUnit::Day,
+        'w' => OffsetUnit::Week,
+        'm' => OffsetUnit::Month,
+        _ => OffsetUnit::Year,
+    };
+    let amount = if sign == '-' { -amount } else { amount };
+    Ok((input, Offset { amount, unit }))
+}
+
+pub fn anchor(input: &str) -> IResult<&str, Anchor> {
+    terminated(
+        alt((
+            tag("eow").map(|_| Anchor::EndOfWeek),
+            tag("eom").map(|_| Anchor::EndOfMonth),
+            tag("eoq").map(|_| Anchor::EndOfQuarter),
+            tag("eoy").map(|_| Anchor::EndOfYear),
+            preceded(tag("next-"), weekday).map(Anchor::NextWeekday),
+            weekday.map(Anchor::Weekday),
+        )),
+        not(peek(satisfy(|c| c.is_alphanumeric()))),
+    )
+    .parse(input)
+}
+
+pub fn weekday(input: &str) -> IResult<&str, Weekday> {
+    alt((
+        tag("mon").map(|_| Weekday::Mon),
+        tag("tue").map(|_| Weekday::Tue),
+        tag("wed").map(|_| Weekday::Wed),
+        tag("thu").map(|_| Weekday::Thu),
+        tag("fri").map(|_| Weekday::Fri),
+        tag("sat").map(|_| Weekday::Sat),
+        tag("sun").map(|_| Weekday::Sun),
+    ))
+    .parse(input)
+}
+
 pub fn four_digits(input: &str) -> IResult<&str, i32> {
     take(4u8).map_res(|x: &str| x.parse::<i32>()).parse(input)
 }
 
+pub fn three_digits(input: &str) -> IResult<&str, u32> {
+    take(3u8).map_res(|x: &str| x.parse::<u32>()).parse(input)
+}
+
 pub fn two_digits(input: &str) -> IResult<&str, u32> {
     take(2u8).map_res(|x: &str| x.parse::<u32>()).parse(input)
 }
 
+pub fn one_digit(input: &str) -> IResult<&str, u32> {
+    satisfy(|c| c.is_ascii_digit())
+        .map(|c| c.to_digit(10).unwrap_or_default())
+        .parse(input)
+}
+
 pub fn hyphen(input: &str) -> IResult<&str, &str> {
     tag("-").parse(input)
 }
@@ -509,7 +1099,10 @@ pub fn cap_t(input: &str) -> IResult<&str, &str> {
 }
 
 #[cfg(test)]
-mod test {
+#[allow(clippy::zero_prefixed_literal)]
+pub mod test {
+    use proptest::{option, prelude::*};
+
     use super::*;
 
     macro_rules! parse_ok {
@@ -526,6 +1119,7 @@ mod test {
     macro_rules! parse_err {
         ($parser:expr, $name:ident, $input:literal) => {
             #[test]
+            #[allow(clippy::redundant_pattern_matching)]
             pub fn $name() {
                 use nom::Parser;
                 let result = $parser.parse($input);
@@ -560,6 +1154,52 @@ mod test {
         );
     }
 
+    mod second_rest {
+        use super::*;
+
+        parse_ok!(second_rest, second, "05", SecondRest::from_s(05));
+        parse_ok!(
+            second_rest,
+            second_fraction,
+            "05.25",
+            SecondRest {
+                second: 05,
+                nanosecond: Some(250_000_000)
+            }
+        );
+        parse_ok!(
+            second_rest,
+            fraction_nanosecond,
+            "05.000000001",
+            SecondRest {
+                second: 05,
+                nanosecond: Some(1)
+            }
+        );
+        parse_err!(fraction, err_too_precise, "0000000001");
+    }
+
+    mod time_offset {
+        use super::*;
+
+        parse_ok!(time_offset, utc, "Z", FixedOffset::east_opt(0).unwrap());
+        parse_ok!(
+            time_offset,
+            east,
+            "+05:30",
+            FixedOffset::east_opt(5 * 3600 + 30 * 60).unwrap()
+        );
+        parse_ok!(
+            time_offset,
+            west,
+            "-08:00",
+            FixedOffset::west_opt(8 * 3600).unwrap()
+        );
+        parse_err!(time_offset, err_minute, "+05:60");
+        parse_err!(time_offset, err_hour, "+24:00");
+        parse_err!(time_offset, err_colon, "+0530");
+    }
+
     mod day_rest {
         use super::*;
 
@@ -593,6 +1233,187 @@ mod test {
         parse_ok!(month_or_day, ok, "10", 10);
         parse_err!(month_or_day, err_h, "10-");
         parse_err!(month_or_day, err_t, "10T");
+        parse_err!(month_or_day, err_digit, "100");
+
+        #[test]
+        fn followed_by_key() {
+            assert_eq!(month_or_day("10;tags=a"), Ok((";tags=a", 10)));
+        }
+    }
+
+    mod offset {
+        use super::*;
+
+        parse_ok!(
+            offset,
+            plus_day,
+            "+3d",
+            Offset {
+                amount: 3,
+                unit: OffsetUnit::Day
+            }
+        );
+        parse_ok!(
+            offset,
+            minus_month,
+            "-1m",
+            Offset {
+                amount: -1,
+                unit: OffsetUnit::Month
+            }
+        );
+        parse_ok!(
+            offset,
+            plus_hours,
+            "+12h",
+            Offset {
+                amount: 12,
+                unit: OffsetUnit::Hour
+            }
+        );
+        parse_err!(offset, err_unsigned, "3d");
+        parse_err!(offset, err_unit, "+3x");
+        parse_err!(offset, err_amount, "+d");
+    }
+
+    mod anchor {
+        use super::*;
+
+        parse_ok!(anchor, weekday, "fri", Anchor::Weekday(Weekday::Fri));
+        parse_ok!(
+            anchor,
+            next_weekday,
+            "next-mon",
+            Anchor::NextWeekday(Weekday::Mon)
+        );
+        parse_ok!(anchor, end_of_week, "eow", Anchor::EndOfWeek);
+        parse_ok!(anchor, end_of_month, "eom", Anchor::EndOfMonth);
+        parse_ok!(anchor, end_of_quarter, "eoq", Anchor::EndOfQuarter);
+        parse_ok!(anchor, end_of_year, "eoy", Anchor::EndOfYear);
+        parse_err!(anchor, err_full_name, "friday");
+        parse_err!(anchor, err_next, "next-eom");
+    }
+
+    mod week_date {
+        use super::*;
+
+        parse_ok!(
+            week_date,
+            week,
+            "2025-W01",
+            WeekDate {
+                year: 2025,
+                week: 1,
+                weekday: None
+            }
+        );
+        parse_err!(week_date, err_three_digits, "2025-W011");
+        parse_err!(week_date, err_weekday_digits, "2025-W01-12");
+
+        #[test]
+        fn date() {
+            let fragment = DateTimeFragment::from_ywd(2025, 11, 3);
+            assert_eq!(fragment.year(), Some(2025));
+            assert_eq!(fragment.month(), Some(3));
+            assert_eq!(fragment.day(), Some(12));
+            assert_eq!(DateTimeFragment::from_ywd(2025, 11, 8).day(), None);
+            assert_eq!(DateTimeFragment::from_yw(2025, 11).month(), None);
+        }
+    }
+
+    mod ordinal_date {
+        use super::*;
+
+        parse_ok!(
+            ordinal_date,
+            ordinal,
+            "2024-366",
+            OrdinalDate {
+                year: 2024,
+                ordinal: 366
+            }
+        );
+        parse_err!(ordinal_date, err_month, "2024-03");
+        parse_err!(ordinal_date, err_four_digits, "2024-0366");
+    }
+
+    mod quarter {
+        use super::*;
+
+        parse_ok!(
+            quarter,
+            second,
+            "2025-Q2",
+            Quarter {
+                year: 2025,
+                quarter: 2
+            }
+        );
+        parse_err!(quarter, err_two_digits, "2025-Q12");
+    }
+
+    mod basic_date_time {
+        use super::*;
+
+        parse_ok!(
+            basic_date_time,
+            date,
+            "20250309",
+            YearRest::from_ymd(2025, 03, 09)
+        );
+        parse_ok!(
+            basic_date_time,
+            hour_minute,
+            "20250309T1030",
+            YearRest::from_ymd_hm(2025, 03, 09, 10, 30)
+        );
+        parse_ok!(
+            basic_date_time,
+            hour_minute_second,
+            "20250309T103005",
+            YearRest::from_ymd_hms(2025, 03, 09, 10, 30, 05)
+        );
+        parse_err!(basic_date_time, err_short, "202503");
+        parse_err!(basic_date_time, err_long, "202503091");
+
+        #[test]
+        fn utc() {
+            let (_, fragment) = basic_date_time("20250309T1030Z").unwrap();
+            assert_eq!(fragment.minute(), Some(30));
+            assert_eq!(
+                fragment.rest.unwrap().rest.unwrap().rest.unwrap().offset,
+                FixedOffset::east_opt(0)
+            );
+        }
+    }
+
+    mod locale_date {
+        use super::*;
+
+        #[test]
+        fn order() {
+            assert_eq!(
+                locale_date("09/03/2025", DateOrder::DayFirst),
+                Ok(("", YearRest::from_ymd(2025, 03, 09)))
+            );
+            assert_eq!(
+                locale_date("03/09/2025T10:30", DateOrder::MonthFirst),
+                Ok(("", YearRest::from_ymd_hm(2025, 03, 09, 10, 30)))
+            );
+        }
+
+        #[test]
+        fn opt_in() {
+            assert!(!matches!(date_time_fragment("09/03/2025"), Ok(("", _))));
+            assert_eq!(
+                date_time_fragment_with("09/03/2025", Some(DateOrder::DayFirst)),
+                Ok(("", DateTimeFragment::from_ymd(2025, 03, 09)))
+            );
+            assert_eq!(
+                date_time_fragment_with("2025-03-09", Some(DateOrder::DayFirst)),
+                Ok(("", DateTimeFragment::from_ymd(2025, 03, 09)))
+            );
+        }
     }
 
     mod year_rest {
@@ -809,5 +1630,179 @@ mod test {
             "T10:20:30",
             DateTimeFragment::from_hms(10, 20, 30)
         );
+        // rfc 3339
+        #[test]
+        fn rfc3339() {
+            let (rest, fragment) = date_time_fragment("2025-10-01T10:20:25.250+05:30").unwrap();
+            assert_eq!(rest, "");
+            assert_eq!(fragment.day(), Some(01));
+            assert_eq!(fragment.second(), Some(25));
+            assert_eq!(fragment.nanosecond(), Some(250_000_000));
+            assert_eq!(fragment.offset(), FixedOffset::east_opt(5 * 3600 + 30 * 60));
+        }
+        parse_ok!(
+            date_time_fragment,
+            t_hour_utc,
+            "T10Z",
+            DateTimeFragment::HourRest(Some(HourRest {
+                offset: FixedOffset::east_opt(0),
+                ..HourRest::from_h(10)
+            }))
+        );
+        // week, ordinal and quarter
+        parse_ok!(
+            date_time_fragment,
+            week,
+            "2025-W11",
+            DateTimeFragment::from_yw(2025, 11)
+        );
+        parse_ok!(
+            date_time_fragment,
+            week_day,
+            "2025-W11-3",
+            DateTimeFragment::from_ywd(2025, 11, 3)
+        );
+        parse_ok!(
+            date_time_fragment,
+            ordinal,
+            "2025-068",
+            DateTimeFragment::from_yo(2025, 68)
+        );
+        parse_ok!(
+            date_time_fragment,
+            quarter,
+            "2025-Q2",
+            DateTimeFragment::from_yq(2025, 2)
+        );
+        // anchor
+        parse_ok!(
+            date_time_fragment,
+            anchor,
+            "eoq",
+            DateTimeFragment::from_anchor(Anchor::EndOfQuarter)
+        );
+        // offset
+        parse_ok!(
+            date_time_fragment,
+            offset,
+            "+2w",
+            DateTimeFragment::from_offset(2, OffsetUnit::Week)
+        );
+    }
+
+    mod display {
+        use super::*;
+
+        #[test]
+        fn canonical() {
+            let cases = [
+                "2025",
+                "2025-03",
+                "2025-03-09T10:30:00.25+05:30",
+                "2025-03-09T10Z",
+                "09",
+                "03-",
+                "03-09T10",
+                "09T",
+                "T",
+                "-1m",
+                "next-fri",
+                "2025-W11-3",
+                "2025-068",
+                "2025-Q2",
+            ];
+            for case in cases {
+                let (_, fragment) = date_time_fragment(case).unwrap();
+                assert_eq!(fragment.to_string(), case);
+            }
+            let (_, fragment) = date_time_fragment("20250309T1030").unwrap();
+            assert_eq!(fragment.to_string(), "2025-03-09T10:30");
+            let (_, fragment) = date_time_fragment("2025-03-09T10:30:00.500+00:00").unwrap();
+            assert_eq!(fragment.to_string(), "2025-03-09T10:30:00.5Z");
+        }
+
+        proptest! {
+            #[test]
+            fn round_trip(fragment in arb_fragment()) {
+                let text = fragment.to_string();
+                prop_assert_eq!(date_time_fragment(&text), Ok(("", fragment)));
+            }
+        }
+    }
+
+    /// Fragments of calendar dates if `valid`, otherwise of any digits the parser accepts.
+    pub fn arb_year_rest(valid: bool) -> impl Strategy<Value = YearRest> {
+        let years = if valid { 1970..2100 } else { 0..10000 };
+        (years, option::of(arb_month_rest(valid))).prop_map(|(year, rest)| YearRest { year, rest })
+    }
+
+    fn arb_month_rest(valid: bool) -> impl Strategy<Value = MonthRest> {
+        let months = if valid { 1..13u32 } else { 0..100 };
+        (months, option::of(arb_day_rest(valid)))
+            .prop_map(|(month, rest)| MonthRest { month, rest })
+    }
+
+    fn arb_day_rest(valid: bool) -> impl Strategy<Value = DayRest> {
+        let days = if valid { 1..29u32 } else { 0..100 };
+        (days, option::of(arb_hour_rest(valid))).prop_map(|(day, rest)| DayRest { day, rest })
+    }
+
+    pub fn arb_hour_rest(valid: bool) -> impl Strategy<Value = HourRest> {
+        let (hours, sixty) = if valid {
+            (0..24u32, 0..60u32)
+        } else {
+            (0..100, 0..100)
+        };
+        let offset = (-(24 * 60 - 1)..24 * 60)
+            .prop_map(|minutes| FixedOffset::east_opt(minutes * 60).unwrap());
+        let second = (sixty.clone(), option::of(0..1_000_000_000u32))
+            .prop_map(|(second, nanosecond)| SecondRest { second, nanosecond });
+        let minute =
+            (sixty, option::of(second)).prop_map(|(minute, rest)| MinuteRest { minute, rest });
+        (hours, option::of(minute), option::of(offset)).prop_map(|(hour, rest, offset)| HourRest {
+            hour,
+            rest,
+            offset,
+        })
+    }
+
+    pub fn arb_fragment() -> impl Strategy<Value = DateTimeFragment> {
+        let unit = prop_oneof![
+            Just(OffsetUnit::Hour),
+            Just(OffsetUnit::Day),
+            Just(OffsetUnit::Week),
+            Just(OffsetUnit::Month),
+            Just(OffsetUnit::Year),
+        ];
+        let weekday = (0..7u8).prop_map(|day| Weekday::try_from(day).unwrap());
+        let anchor = prop_oneof![
+            weekday.clone().prop_map(Anchor::Weekday),
+            weekday.prop_map(Anchor::NextWeekday),
+            Just(Anchor::EndOfWeek),
+            Just(Anchor::EndOfMonth),
+            Just(Anchor::EndOfQuarter),
+            Just(Anchor::EndOfYear),
+        ];
+        prop_oneof![
+            arb_year_rest(false).prop_map(DateTimeFragment::YearRest),
+            (0..100u32).prop_map(DateTimeFragment::MonthOrDay),
+            arb_month_rest(false).prop_map(DateTimeFragment::MonthRest),
+            arb_day_rest(false).prop_map(DateTimeFragment::DayRest),
+            option::of(arb_hour_rest(false)).prop_map(DateTimeFragment::HourRest),
+            (-1000..1000, unit)
+                .prop_map(|(amount, unit)| DateTimeFragment::from_offset(amount, unit)),
+            anchor.prop_map(DateTimeFragment::from_anchor),
+            (0..10000, 0..100u32, option::of(0..10u32)).prop_map(|(year, week, weekday)| {
+                DateTimeFragment::Week(WeekDate {
+                    year,
+                    week,
+                    weekday,
+                })
+            }),
+            (0..10000, 0..1000u32)
+                .prop_map(|(year, ordinal)| DateTimeFragment::from_yo(year, ordinal)),
+            (0..10000, 0..10u32)
+                .prop_map(|(year, quarter)| DateTimeFragment::from_yq(year, quarter)),
+        ]
     }
 }
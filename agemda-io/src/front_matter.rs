@@ -0,0 +1,97 @@
+use std::collections::BTreeMap;
+
+/// Parse the yaml front matter into flat key value pairs.
+///
+/// Only the top level of the yaml is considered:
+/// scalar values are kept with their quotes stripped,
+/// flow (`[a, b]`) and block (`- item` lines) sequences are joined by `,`,
+/// and nested mappings are ignored.
+pub fn parse_front_matter(text: &str) -> BTreeMap<String, String> {
+    let mut fields = BTreeMap::new();
+    // the key of the last top level line, for collecting sequence items
+    let mut last_key: Option<String> = None;
+
+    for line in text.lines() {
+        let trimmed = line.trim();
+        // skip blank and comment lines
+        if trimmed.is_empty() || trimmed.starts_with('#') {
+            continue;
+        }
+
+        // indented lines belong to the last key
+        if line.starts_with([' ', '\t']) || trimmed.starts_with("- ") || trimmed == "-" {
+            if let (Some(key), Some(item)) = (&last_key, trimmed.strip_prefix('-')) {
+                let item = unquote(item.trim());
+                let value: &mut String = fields.entry(key.clone()).or_default();
+                if !value.is_empty() {
+                    value.push(',');
+                }
+                value.push_str(item);
+            }
+            continue;
+        }
+
+        match line.split_once(':') {
+            Some((key, value)) => {
+                let key = key.trim().to_string();
+                fields.insert(key.clone(), scalar_or_flow(value.trim()));
+                last_key = Some(key);
+            }
+            None => last_key = None,
+        }
+    }
+
+    fields
+}
+
+fn scalar_or_flow(value: &str) -> String {
+    match value.strip_prefix('[').and_then(|v| v.strip_suffix(']')) {
+        Some(items) => items
+            .split(',')
+            .map(|item| unquote(item.trim()))
+            .filter(|item| !item.is_empty())
+            .collect::<Vec<_>>()
+            .join(","),
+        None => unquote(value).to_string(),
+    }
+}
+
+fn unquote(value: &str) -> &str {
+    for quote in ['"', '\''] {
+        if let Some(inner) = value
+            .strip_prefix(quote)
+            .and_then(|v| v.strip_suffix(quote))
+        {
+            return inner;
+        }
+    }
+    value
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn scalars() {
+        let fields = parse_front_matter("date: 2025-03-09\nproject: 'Acme'\nagmd: \"2025-03\"\n");
+        assert_eq!(fields["date"], "2025-03-09");
+        assert_eq!(fields["project"], "Acme");
+        assert_eq!(fields["agmd"], "2025-03");
+    }
+
+    #[test]
+    fn sequences() {
+        let fields = parse_front_matter("tags:\n  - ops\n  - review\nalias: [a, 'b']\n");
+        assert_eq!(fields["tags"], "ops,review");
+        assert_eq!(fields["alias"], "a,b");
+    }
+
+    #[test]
+    fn nested_mapping_ignored() {
+        let fields = parse_front_matter("author:\n  name: someone\n# comment\n\nkey: value\n");
+        assert_eq!(fields["author"], "");
+        assert_eq!(fields["key"], "value");
+        assert_eq!(fields.len(), 2);
+    }
+}
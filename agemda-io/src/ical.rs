@@ -0,0 +1,307 @@
+use std::io::{self, Write};
+
+use agemda_core::{Alarm, Related, Status, Todo, Trigger};
+use chrono::{DateTime, TimeZone, Utc};
+
+use crate::duration::format_duration;
+
+/// Write the todos matching `filter` as an iCalendar of VTODO components.
+///
+/// Todos with malformed link are skipped.
+pub fn write_calendar(
+    w: &mut impl Write,
+    todos: &[Todo],
+    filter: impl Fn(&Todo) -> bool,
+) -> io::Result<()> {
+    let stamp = format_date_time(&Utc::now());
+
+    write_line(w, "BEGIN", "VCALENDAR")?;
+    write_line(w, "VERSION", "2.0")?;
+    write_line(w, "PRODID", "-//agemda//agmd//EN")?;
+    let exported = |todo: &Todo| todo.attributes.is_ok() && filter(todo);
+    for (index, todo) in todos.iter().enumerate().filter(|(_, todo)| filter(todo)) {
+        let Ok(agmd) = &todo.attributes else {
+            continue;
+        };
+        write_line(w, "BEGIN", "VTODO")?;
+        write_line(w, "UID", &uid(todos, index))?;
+        write_line(w, "DTSTAMP", &stamp)?;
+        write_line(w, "SUMMARY", &escape(&todo.summary.plain()))?;
+        if let Some(description) = &todo.description {
+            write_line(w, "DESCRIPTION", &escape(&description.plain))?;
+        }
+        if let Some(start) = agmd.start {
+            write_line(w, "DTSTART", &format_date_time(&start))?;
+        }
+        if let Some(due) = agmd.due {
+            write_line(w, "DUE", &format_date_time(&due))?;
+        }
+        if let Some(completed) = agmd.completed {
+            write_line(w, "COMPLETED", &format_date_time(&completed))?;
+        }
+        write_line(w, "STATUS", agmd.status.vtodo())?;
+        if matches!(agmd.status, Status::Deferred | Status::Question) {
+            write_line(w, "X-AGMD-STATUS", &agmd.status.name().to_uppercase())?;
+        }
+        if !todo.tags.is_empty() {
+            let categories: Vec<_> = todo.tags.iter().map(|tag| escape(tag)).collect();
+            write_line(w, "CATEGORIES", &categories.join(","))?;
+        }
+        for assignee in &todo.assignees {
+            write_line(w, &attendee_name(assignee), &attendee_address(assignee))?;
+        }
+        if let Some(priority) = agmd.priority {
+            write_line(w, "PRIORITY", &priority.to_string())?;
+        }
+        // the parent is referred only if exported along
+        if let Some(parent) = todo.parent.filter(|&parent| exported(&todos[parent])) {
+            write_line(w, "RELATED-TO;RELTYPE=PARENT", &uid(todos, parent))?;
+        }
+        for &prerequisite in &todo.prerequisites {
+            write_line(
+                w,
+                "RELATED-TO;RELTYPE=DEPENDS-ON",
+                &uid(todos, prerequisite),
+            )?;
+        }
+        if !todo.metadata.headings.is_empty() {
+            write_line(w, "X-AGMD-CONTEXT", &escape(&todo.metadata.context()))?;
+        }
+        write_line(
+            w,
+            "X-AGMD-PATH",
+            &escape(&todo.metadata.path.to_string_lossy()),
+        )?;
+        for (key, value) in &agmd.extensions {
+            write_line(w, &extension_name(key), &escape(value))?;
+        }
+        for alarm in agmd.alarms.iter().filter(|alarm| alarm.time.is_some()) {
+            write_alarm(w, alarm, &todo.summary.plain())?;
+        }
+        write_line(w, "END", "VTODO")?;
+    }
+    write_line(w, "END", "VCALENDAR")
+}
+
+/// Write a VALARM component displaying the summary.
+fn write_alarm(w: &mut impl Write, alarm: &Alarm, summary: &str) -> io::Result<()> {
+    write_line(w, "BEGIN", "VALARM")?;
+    write_line(w, "ACTION", "DISPLAY")?;
+    write_line(w, "DESCRIPTION", &escape(summary))?;
+    match &alarm.trigger {
+        Trigger::Relative { related, offset } => {
+            let related = match related {
+                Related::Start => "START",
+                Related::Due => "END",
+            };
+            write_line(
+                w,
+                &format!("TRIGGER;RELATED={related}"),
+                &format_duration(*offset),
+            )?;
+        }
+        Trigger::Absolute(time) => {
+            write_line(w, "TRIGGER;VALUE=DATE-TIME", &format_date_time(time))?;
+        }
+    }
+    write_line(w, "END", "VALARM")
+}
+
+/// The UID of the todo at index, from its own uid or derived from its path,
+/// headings and summary, and its occurrence among the todos of the same summary under the headings.
+///
+/// The derived one is stable across builds, for calendars to track the todo between exports.
+pub fn uid(todos: &[Todo], index: usize) -> String {
+    let todo = &todos[index];
+    if let Some(uid) = &todo.uid {
+        return format!("{uid}@agemda");
+    }
+    let metadata = &todo.metadata;
+    let summary = todo.summary.plain();
+    let occurrence = todos[..index]
+        .iter()
+        .filter(|other| {
+            other.metadata.path == metadata.path
+                && other.metadata.headings == metadata.headings
+                && other.summary.plain() == summary
+        })
+        .count();
+    // zero separates path, headings and summary, and one the occurrence after the first
+    let mut bytes = metadata.path.as_os_str().as_encoded_bytes().to_vec();
+    for heading in &metadata.headings {
+        bytes.push(0);
+        bytes.extend_from_slice(heading.as_bytes());
+    }
+    bytes.push(0);
+    bytes.extend_from_slice(summary.as_bytes());
+    if occurrence > 0 {
+        bytes.push(1);
+        bytes.extend_from_slice(occurrence.to_string().as_bytes());
+    }
+    let hash = fnv1a(&bytes);
+    format!("{hash:016x}@agemda")
+}
+
+/// The 64-bit FNV-1a hash of bytes.
+fn fnv1a<'a>(bytes: impl IntoIterator<Item = &'a u8>) -> u64 {
+    const OFFSET_BASIS: u64 = 0xcbf29ce484222325;
+    const PRIME: u64 = 0x100000001b3;
+    bytes.into_iter().fold(OFFSET_BASIS, |hash, &byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(PRIME)
+    })
+}
+
+/// The ATTENDEE property name with the common name parameter.
+fn attendee_name(assignee: &str) -> String {
+    format!("ATTENDEE;CN=\"{}\"", assignee.replace('"', "'"))
+}
+
+/// The experimental property name of extension key, e.g. `X-AGMD-TICKET` of `ticket`,
+/// with characters other than ASCII alphanumerics as `-`.
+fn extension_name(key: &str) -> String {
+    let name: String = key
+        .chars()
+        .map(|c| match c.is_ascii_alphanumeric() {
+            true => c.to_ascii_uppercase(),
+            false => '-',
+        })
+        .collect();
+    format!("X-AGMD-{name}")
+}
+
+/// The calendar user address of assignee, a mailto for email address.
+fn attendee_address(assignee: &str) -> String {
+    match assignee.contains('@') {
+        true => format!("mailto:{assignee}"),
+        false => format!("urn:agemda:assignee:{assignee}"),
+    }
+}
+
+fn format_date_time<Tz: TimeZone>(date_time: &DateTime<Tz>) -> String {
+    date_time
+        .with_timezone(&Utc)
+        .format("%Y%m%dT%H%M%SZ")
+        .to_string()
+}
+
+/// Escape TEXT value.
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '\\' | ';' | ',' => {
+                escaped.push('\\');
+                escaped.push(c);
+            }
+            '\n' => escaped.push_str("\\n"),
+            '\r' => {}
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// Write a content line, folded at 75 octets.
+fn write_line(w: &mut impl Write, name: &str, value: &str) -> io::Result<()> {
+    let line = format!("{name}:{value}");
+    let mut rest = line.as_str();
+    let mut limit = 75;
+    while rest.len() > limit {
+        let mut end = limit;
+        while !rest.is_char_boundary(end) {
+            end -= 1;
+        }
+        write!(w, "{}\r\n ", &rest[..end])?;
+        rest = &rest[end..];
+        // the leading space of continuation takes an octet
+        limit = 74;
+    }
+    write!(w, "{rest}\r\n")
+}
+
+#[cfg(test)]
+mod test {
+    use crate::parse::{ParseOptions, parse_text};
+
+    use super::*;
+
+    #[test]
+    fn escape_text() {
+        assert_eq!(escape("a;b,c\\d\ne"), "a\\;b\\,c\\\\d\\ne");
+    }
+
+    #[test]
+    fn derived_uid() {
+        // the reference values of FNV-1a
+        assert_eq!(fnv1a(b""), 0xcbf29ce484222325);
+        assert_eq!(fnv1a(b"a"), 0xaf63dc4c8601ec8c);
+        assert_eq!(fnv1a(b"foobar"), 0x85944171f73967e8);
+
+        let mut todos = vec![];
+        parse_text(
+            &mut todos,
+            "notes/a.md",
+            "- [ ] release <agmd:>\n",
+            &ParseOptions::default(),
+        );
+        assert_eq!(uid(&todos, 0), "2029843f2c855514@agemda");
+    }
+
+    #[test]
+    fn duplicate_summary() {
+        let mut todos = vec![];
+        parse_text(
+            &mut todos,
+            "notes/a.md",
+            "# A\n- [ ] review <agmd:>\n- [ ] review <agmd:>\n  - [ ] review\n# B\n- [ ] review <agmd:>\n",
+            &ParseOptions::default(),
+        );
+        let uids: Vec<_> = (0..todos.len()).map(|index| uid(&todos, index)).collect();
+        for (index, uid) in uids.iter().enumerate() {
+            assert!(!uids[..index].contains(uid), "duplicate {uid}");
+        }
+    }
+
+    #[test]
+    fn parent_not_exported() {
+        let mut todos = vec![];
+        parse_text(
+            &mut todos,
+            "notes/a.md",
+            "- [x] a <agmd:>\n  - [ ] b\n",
+            &ParseOptions::default(),
+        );
+        let mut buf = vec![];
+        write_calendar(&mut buf, &todos, |todo| todo.summary.plain() == "b").unwrap();
+        let text = String::from_utf8(buf).unwrap();
+        assert!(text.contains("SUMMARY:b"));
+        assert!(!text.contains("RELATED-TO"));
+
+        let mut buf = vec![];
+        write_calendar(&mut buf, &todos, |_| true).unwrap();
+        let text = String::from_utf8(buf).unwrap();
+        assert!(text.contains(&format!("RELATED-TO;RELTYPE=PARENT:{}", uid(&todos, 0))));
+    }
+
+    #[test]
+    fn extension_property() {
+        assert_eq!(extension_name("ticket"), "X-AGMD-TICKET");
+        assert_eq!(extension_name("review_url"), "X-AGMD-REVIEW-URL");
+        assert_eq!(extension_name("jira.key"), "X-AGMD-JIRA-KEY");
+    }
+
+    #[test]
+    fn fold_line() {
+        let mut buf = vec![];
+        let value = "x".repeat(150);
+        write_line(&mut buf, "SUMMARY", &value).unwrap();
+        let text = String::from_utf8(buf).unwrap();
+        let lines: Vec<_> = text.split("\r\n").collect();
+        assert_eq!(lines.len(), 4);
+        assert_eq!(lines[0].len(), 75);
+        assert!(lines[1].starts_with(' '));
+        assert_eq!(lines[1].len(), 75);
+        assert_eq!(lines[3], "");
+        assert_eq!(text.replace("\r\n ", ""), format!("SUMMARY:{value}\r\n"));
+    }
+}
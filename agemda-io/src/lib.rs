@@ -1,5 +1,13 @@
+pub mod check;
+pub mod clock;
 pub mod convert;
+pub mod duration;
+pub mod edit;
 pub mod fragment;
+pub mod front_matter;
+pub mod ical;
 pub mod link;
 pub mod load;
 pub mod parse;
+pub mod pattern;
+pub mod uid;
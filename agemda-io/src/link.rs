@@ -1,24 +1,471 @@
 use nom::{
     IResult, Parser,
-    bytes::{complete::tag, take_until},
-    combinator::{eof, opt},
-    multi::{many0, separated_list0},
-    sequence::{preceded, terminated},
+    branch::alt,
+    bytes::complete::{escaped_transform, tag, take_till, take_till1, take_until},
+    character::complete::{none_of, one_of},
+    combinator::{eof, opt, peek, value},
+    multi::{many0, separated_list0, separated_list1},
+    sequence::{delimited, preceded, terminated},
 };
 
-use crate::fragment::{DateTimeFragment, date_time_fragment};
+use std::fmt;
+
+use agemda_core::{Attributes, Related, Trigger};
+use chrono::{
+    DateTime, Datelike, FixedOffset, Local, NaiveDateTime, Offset as _, TimeDelta, Timelike,
+};
+
+use crate::{
+    convert::{Role, fragment_to_datetime_both, fragment_to_datetime_either},
+    duration::{duration, format_duration},
+    fragment::{
+        DateOrder, DateTimeFragment, DayRest, HourRest, MinuteRest, MonthRest, SecondRest,
+        WeekDate, YearRest, date_time_fragment_with,
+    },
+};
 
 /// The structure of agmd link
+#[derive(Clone, Debug, Default, PartialEq)]
 pub struct Link {
     pub base: Option<DateTimeFragment>,
     pub start: Option<DateTimeFragment>,
     pub due: Option<DateTimeFragment>,
     pub completed: Option<DateTimeFragment>,
+    /// Priority in VTODO scale, 1 for the highest and 9 for the lowest.
+    pub priority: Option<u8>,
+    pub tags: Vec<String>,
+    /// Names of assignees, replacing the inherited ones.
+    pub assignees: Vec<String>,
+    /// The stable identifier of the todo, never inherited.
+    pub uid: Option<String>,
+    /// Uids of the todos this todo depends on, never inherited.
+    pub depends: Vec<String>,
+    pub alarms: Vec<AlarmSpec>,
+    /// The estimated time of work, never inherited.
+    pub estimate: Option<TimeDelta>,
+    /// The time of work spent, never inherited.
+    pub spent: Option<TimeDelta>,
+    /// Pairs of keys unknown to agmd and their values, in order.
+    ///
+    /// Inherited, with own values replacing the inherited ones of the same keys.
+    pub extensions: Vec<(String, String)>,
+}
+
+/// An alarm as written in link, e.g. `-PT15M`, `start-PT1H` or `2025-03-09T09`.
+#[derive(Clone, Debug, PartialEq)]
+pub enum AlarmSpec {
+    /// An offset from the given date, or due if any, otherwise start.
+    Relative {
+        related: Option<Related>,
+        offset: TimeDelta,
+    },
+    /// A fragment resolved with the base of link.
+    Absolute(DateTimeFragment),
+}
+
+/// The value of key value pair in link.
+#[derive(Clone, Debug, PartialEq)]
+enum Value {
+    Fragment(DateTimeFragment),
+    Priority(u8),
+    List(Vec<String>),
+    Text(String),
+    Alarms(Vec<AlarmSpec>),
+    Duration(TimeDelta),
+    Extension(String),
+}
+
+impl Link {
+    /// Fill this link from the inherited `defaults`.
+    ///
+    /// A link with a base of its own keeps its dates but inherits priority, tags, assignees,
+    /// alarms and extensions,
+    /// otherwise the base and any missing `start` or `due` are inherited.
+    pub fn with_defaults(self, defaults: &Link) -> Link {
+        let priority = self.priority.or(defaults.priority);
+        let tags = self
+            .tags
+            .into_iter()
+            .fold(defaults.tags.clone(), |mut tags, tag| {
+                push_unique(&mut tags, tag);
+                tags
+            });
+        let assignees = match self.assignees.is_empty() {
+            true => defaults.assignees.clone(),
+            false => self.assignees,
+        };
+        let alarms = match self.alarms.is_empty() {
+            true => defaults.alarms.clone(),
+            false => self.alarms,
+        };
+        let extensions = self.extensions.into_iter().fold(
+            defaults.extensions.clone(),
+            |mut extensions, (key, value)| {
+                insert_extension(&mut extensions, key, value);
+                extensions
+            },
+        );
+        if self.base.is_some() {
+            return Link {
+                priority,
+                tags,
+                assignees,
+                alarms,
+                extensions,
+                ..self
+            };
+        }
+        Link {
+            base: defaults.base.clone(),
+            start: self.start.or_else(|| defaults.start.clone()),
+            due: self.due.or_else(|| defaults.due.clone()),
+            priority,
+            tags,
+            assignees,
+            alarms,
+            extensions,
+            ..self
+        }
+    }
+
+    /// The canonical form of this link, with `start`, `due`, `completed` and absolute alarms
+    /// reduced to the components differing from the base, e.g. `2025-03-09..12`.
+    ///
+    /// `start` and `due` the same as the base are dropped, as they resolve to the base anyway.
+    /// Links whose base is not a calendar date, like `fri` or `2025-W11`, are kept as is.
+    pub fn canonical(self) -> Link {
+        let base = match &self.base {
+            Some(
+                base @ (DateTimeFragment::YearRest(_)
+                | DateTimeFragment::Week(WeekDate {
+                    weekday: Some(_), ..
+                })
+                | DateTimeFragment::Ordinal(_)),
+            ) => base.clone(),
+            _ => return self,
+        };
+        Link {
+            start: self.start.and_then(|start| reduce(start, &base, false)),
+            due: self.due.and_then(|due| reduce(due, &base, false)),
+            completed: self
+                .completed
+                .and_then(|completed| reduce(completed, &base, true)),
+            alarms: self
+                .alarms
+                .into_iter()
+                .filter_map(|alarm| match alarm {
+                    AlarmSpec::Absolute(fragment) => {
+                        reduce(fragment, &base, true).map(AlarmSpec::Absolute)
+                    }
+                    relative => Some(relative),
+                })
+                .collect(),
+            ..self
+        }
+    }
+
+    /// The canonical link of resolved attributes, with dates exact to the second.
+    ///
+    /// The coarser of start and due is the base if there are both,
+    /// as a link with base always resolves both of them,
+    /// and those resolving the same as the base are dropped, e.g. `2025-03` for the whole month.
+    pub fn from_attributes(attributes: &Attributes) -> Link {
+        let base = match (attributes.start, attributes.due) {
+            (Some(start), Some(due)) => {
+                let start = exact_fragment(start, &None, Role::Start);
+                let due = exact_fragment(due, &None, Role::End);
+                Some(match precision(&due) < precision(&start) {
+                    true => due,
+                    false => start,
+                })
+            }
+            _ => None,
+        };
+        let exact = |date_time, role| exact_fragment(date_time, &base, role);
+        let of_base = |date_time, role| {
+            let implied = base
+                .as_ref()
+                .and_then(|base| fragment_to_datetime_either(base, role));
+            (implied != Some(date_time)).then(|| exact(date_time, role))
+        };
+        Link {
+            start: attributes
+                .start
+                .and_then(|start| of_base(start, Role::Start)),
+            due: attributes.due.and_then(|due| of_base(due, Role::End)),
+            completed: attributes
+                .completed
+                .map(|completed| exact(completed, Role::End)),
+            priority: attributes.priority,
+            alarms: attributes
+                .alarms
+                .iter()
+                .map(|alarm| match alarm.trigger {
+                    Trigger::Relative { related, offset } => AlarmSpec::Relative {
+                        related: Some(related),
+                        offset,
+                    },
+                    Trigger::Absolute(time) => AlarmSpec::Absolute(exact(time, Role::Start)),
+                })
+                .collect(),
+            estimate: attributes.estimate,
+            spent: attributes.spent,
+            extensions: attributes.extensions.clone(),
+            base,
+            ..Link::default()
+        }
+        .canonical()
+    }
+}
+
+/// The text of link after `agmd:`, keys in a fixed order and empty ones skipped.
+///
+/// A due without start is the end of range `A..B` if it reads back the same.
+impl fmt::Display for Link {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let range_end = match (&self.base, &self.start, &self.due) {
+            (Some(base), None, Some(due)) => range_end_text(due, base),
+            _ => None,
+        };
+        let mut parts: Vec<String> = self
+            .base
+            .iter()
+            .map(|base| match &range_end {
+                Some(end) => format!("{base}..{end}"),
+                None => base.to_string(),
+            })
+            .collect();
+        let due = match range_end {
+            Some(_) => &None,
+            None => &self.due,
+        };
+        let fragments = [
+            ("start", &self.start),
+            ("due", due),
+            ("completed", &self.completed),
+        ];
+        for (key, fragment) in fragments {
+            if let Some(fragment) = fragment {
+                parts.push(format!("{key}={fragment}"));
+            }
+        }
+        if let Some(priority) = self.priority {
+            parts.push(format!("priority={priority}"));
+        }
+        let lists = [("tags", &self.tags), ("assignee", &self.assignees)];
+        for (key, list) in lists {
+            if !list.is_empty() {
+                parts.push(format!("{key}={}", list.join(",")));
+            }
+        }
+        if let Some(uid) = &self.uid {
+            parts.push(format!("uid={uid}"));
+        }
+        if !self.depends.is_empty() {
+            parts.push(format!("depends={}", self.depends.join(",")));
+        }
+        if !self.alarms.is_empty() {
+            let alarms: Vec<_> = self.alarms.iter().map(ToString::to_string).collect();
+            parts.push(format!("alarm={}", alarms.join(",")));
+        }
+        let durations = [("estimate", self.estimate), ("spent", self.spent)];
+        for (key, delta) in durations {
+            if let Some(delta) = delta {
+                parts.push(format!("{key}={}", format_duration(delta)));
+            }
+        }
+        for (key, value) in &self.extensions {
+            parts.push(format!("{key}={}", quote(value)));
+        }
+        write!(f, "{}", parts.join(";"))
+    }
+}
+
+impl fmt::Display for AlarmSpec {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AlarmSpec::Relative { related, offset } => {
+                let related = match related {
+                    Some(Related::Start) => "start",
+                    Some(Related::Due) => "due",
+                    None => "",
+                };
+                write!(f, "{related}{}", format_duration(*offset))
+            }
+            AlarmSpec::Absolute(fragment) => write!(f, "{fragment}"),
+        }
+    }
+}
+
+/// The fragment reduced to the components from the first one differing from the base,
+/// e.g. `2025-03-12` to `12` over `2025-03-09`.
+///
+/// The same fragment as the base is dropped, or kept as its finest component if `keep`.
+fn reduce(
+    fragment: DateTimeFragment,
+    base: &DateTimeFragment,
+    keep: bool,
+) -> Option<DateTimeFragment> {
+    let reduced = match &fragment {
+        DateTimeFragment::YearRest(year_rest) => reduce_year_rest(year_rest, base, keep)?,
+        _ => fragment,
+    };
+    // a bare day or month is read by the base
+    Some(match reduced {
+        DateTimeFragment::DayRest(DayRest { day, rest: None }) if base.month().is_some() => {
+            DateTimeFragment::MonthOrDay(day)
+        }
+        DateTimeFragment::MonthRest(MonthRest { month, rest: None }) if base.month().is_none() => {
+            DateTimeFragment::MonthOrDay(month)
+        }
+        reduced => reduced,
+    })
+}
+
+fn reduce_year_rest(
+    year_rest: &YearRest,
+    base: &DateTimeFragment,
+    keep: bool,
+) -> Option<DateTimeFragment> {
+    // components of year, month, day, hour with offset, minute and second with fraction
+    let components = |fragment: &DateTimeFragment| {
+        let plain = |value: Option<u32>| value.map(|value| (i64::from(value), None));
+        [
+            fragment.year().map(|year| (i64::from(year), None)),
+            plain(fragment.month()),
+            plain(fragment.day()),
+            fragment.hour().map(|hour| {
+                let offset = fragment.offset().map(|offset| offset.local_minus_utc());
+                (i64::from(hour), offset.map(i64::from))
+            }),
+            plain(fragment.minute()),
+            fragment
+                .second()
+                .map(|second| (i64::from(second), fragment.nanosecond().map(i64::from))),
+        ]
+    };
+    let fragment = DateTimeFragment::YearRest(year_rest.clone());
+    let (ours, theirs) = (components(&fragment), components(base));
+    let finest = ours.iter().rposition(Option::is_some)?;
+    let level = match (0..=finest).find(|&level| ours[level] != theirs[level]) {
+        Some(level) => level,
+        None if keep => finest,
+        None => return None,
+    };
+    let month_rest = year_rest.rest.as_ref();
+    let day_rest = month_rest.and_then(|month_rest| month_rest.rest.as_ref());
+    Some(match (level, month_rest, day_rest) {
+        (1, Some(month_rest), _) => DateTimeFragment::MonthRest(month_rest.clone()),
+        (2, _, Some(day_rest)) => DateTimeFragment::DayRest(day_rest.clone()),
+        (3.., _, Some(day_rest)) => DateTimeFragment::HourRest(day_rest.rest.clone()),
+        _ => fragment,
+    })
+}
+
+/// Number of components of the fragment finer than year.
+fn precision(fragment: &DateTimeFragment) -> usize {
+    [
+        fragment.month().is_some(),
+        fragment.day().is_some(),
+        fragment.hour().is_some(),
+        fragment.minute().is_some(),
+        fragment.second().is_some(),
+    ]
+    .into_iter()
+    .filter(|&is_some| is_some)
+    .count()
+}
+
+/// The coarsest fragment of a year, a month, a day, a minute or a second
+/// resolving to `date_time` over the base,
+/// or the second with offset if the local time is ambiguous.
+///
+/// An end is exact to the day or the second, as the end of a minute reads one minute early.
+fn exact_fragment(
+    date_time: DateTime<Local>,
+    base: &Option<DateTimeFragment>,
+    role: Role,
+) -> DateTimeFragment {
+    // the end of a day is the next midnight
+    let day = match role {
+        Role::Start => date_time.date_naive(),
+        Role::End => (date_time - TimeDelta::nanoseconds(1)).date_naive(),
+    };
+    let minute = (role == Role::Start).then(|| {
+        DateTimeFragment::from_ymd_hm(
+            date_time.year(),
+            date_time.month(),
+            date_time.day(),
+            date_time.hour(),
+            date_time.minute(),
+        )
+    });
+    // a whole year or month only over a base without finer fields to take
+    let coarse = |fragment: DateTimeFragment| {
+        base.as_ref()
+            .is_none_or(|base| precision(base) <= precision(&fragment))
+            .then_some(fragment)
+    };
+    let candidates = [
+        coarse(DateTimeFragment::from_y(day.year())),
+        coarse(DateTimeFragment::from_ym(day.year(), day.month())),
+        Some(DateTimeFragment::from_ymd(
+            day.year(),
+            day.month(),
+            day.day(),
+        )),
+        minute,
+        Some(second_fragment(date_time.naive_local(), None)),
+    ];
+    let resolve = |fragment: &DateTimeFragment| match base {
+        Some(base) => fragment_to_datetime_both(fragment, base, role),
+        None => fragment_to_datetime_either(fragment, role),
+    };
+    candidates
+        .into_iter()
+        .flatten()
+        .find(|fragment| resolve(fragment) == Some(date_time))
+        .unwrap_or_else(|| second_fragment(date_time.naive_local(), Some(date_time.offset().fix())))
+}
+
+fn second_fragment(naive: NaiveDateTime, offset: Option<FixedOffset>) -> DateTimeFragment {
+    let nanosecond = naive.nanosecond();
+    DateTimeFragment::YearRest(YearRest {
+        year: naive.year(),
+        rest: Some(MonthRest {
+            month: naive.month(),
+            rest: Some(DayRest {
+                day: naive.day(),
+                rest: Some(HourRest {
+                    hour: naive.hour(),
+                    rest: Some(MinuteRest {
+                        minute: naive.minute(),
+                        rest: Some(SecondRest {
+                            second: naive.second(),
+                            nanosecond: (nanosecond != 0).then_some(nanosecond),
+                        }),
+                    }),
+                    offset,
+                }),
+            }),
+        }),
+    })
 }
 
 pub fn link(input: &str) -> IResult<&str, Link> {
-    // parse base
-    let (input, base) = opt(date_time_fragment).parse(input)?;
+    link_with(input, None)
+}
+
+/// Parse the link, also accepting locale dates like `09/03/2025` of the order if given.
+pub fn link_with(input: &str, order: Option<DateOrder>) -> IResult<&str, Link> {
+    let fragment = |input| date_time_fragment_with(input, order);
+    let key_value_pair = |input| key_value_pair(input, order);
+    // parse base, or range of base and due
+    let (input, base) = opt(fragment).parse(input)?;
+    let (input, range_end) = match base {
+        Some(_) => opt(preceded(tag(".."), fragment)).parse(input)?,
+        None => (input, None),
+    };
     // parse kvs
     let (input, kvs) = if base.is_some() {
         many0(preceded(semicolon, key_value_pair)).parse(input)?
@@ -28,65 +475,730 @@ pub fn link(input: &str) -> IResult<&str, Link> {
     // ensure that agmd parse all
     let (input, _) = eof(input)?;
 
-    // collect all fragments
-    let (start, due, completed) = collect_fragments(kvs);
+    // collect all values
+    let mut link = Link {
+        due: range_end.map(|end| range_end_of(end, base.as_ref())),
+        base,
+        ..Link::default()
+    };
+    for (key, value) in kvs.into_iter().flatten() {
+        match (key, value) {
+            ("start", Value::Fragment(fragment)) => link.start = Some(fragment),
+            ("due", Value::Fragment(fragment)) => link.due = Some(fragment),
+            ("completed", Value::Fragment(fragment)) => link.completed = Some(fragment),
+            ("priority", Value::Priority(priority)) => link.priority = Some(priority),
+            ("tags", Value::List(tags)) => link.tags = tags,
+            ("assignee", Value::List(assignees)) => link.assignees = assignees,
+            ("uid", Value::Text(uid)) => link.uid = Some(uid),
+            ("depends", Value::List(depends)) => link.depends = depends,
+            ("alarm", Value::Alarms(alarms)) => link.alarms = alarms,
+            ("estimate", Value::Duration(estimate)) => link.estimate = Some(estimate),
+            ("spent", Value::Duration(spent)) => link.spent = Some(spent),
+            (key, Value::Extension(value)) => {
+                insert_extension(&mut link.extensions, key.to_string(), value)
+            }
+            _ => {}
+        }
+    }
+
+    Ok((input, link))
+}
 
-    Ok((
-        input,
-        Link {
-            base,
-            start,
-            due,
-            completed,
-        },
-    ))
+/// The end of range `A..B`, a bare two digits of the finest of month, day and hour of `A`,
+/// e.g. the day of `2025-03-09..12`, the month of `2025-03..05`
+/// and the hour of `2025-03-09T10:30..12`.
+///
+/// Unlike the end of range, a bare `due` over a base with an hour is still a day,
+/// e.g. `2025-03-09T10:30;due=10` is due the end of 10th, not of 10 o'clock.
+/// Bare two digits already read so as `due` are kept as is.
+fn range_end_of(end: DateTimeFragment, start: Option<&DateTimeFragment>) -> DateTimeFragment {
+    let DateTimeFragment::MonthOrDay(bare) = end else {
+        return end;
+    };
+    match start {
+        Some(start) if start.hour().is_some() => DateTimeFragment::from_h(bare),
+        Some(start) if start.month().is_some() && start.day().is_none() => {
+            DateTimeFragment::from_m(bare)
+        }
+        _ => end,
+    }
+}
+
+/// The text of due as the end of range over the base, if it reads back the same,
+/// e.g. `05` of `2025-03..05`.
+fn range_end_text(due: &DateTimeFragment, base: &DateTimeFragment) -> Option<String> {
+    // bare two digits are read by the base
+    let read = |bare| range_end_of(DateTimeFragment::MonthOrDay(bare), Some(base));
+    match due {
+        DateTimeFragment::MonthOrDay(bare) => (read(*bare) == *due).then(|| due.to_string()),
+        DateTimeFragment::MonthRest(MonthRest {
+            month: bare,
+            rest: None,
+        })
+        | DateTimeFragment::DayRest(DayRest {
+            day: bare,
+            rest: None,
+        })
+        | DateTimeFragment::HourRest(Some(HourRest {
+            hour: bare,
+            rest: None,
+            offset: None,
+        })) if read(*bare) == *due => Some(format!("{bare:02}")),
+        due => Some(due.to_string()),
+    }
 }
 
-fn key_value_pair(input: &str) -> IResult<&str, Option<(&str, DateTimeFragment)>> {
+fn key_value_pair(input: &str, order: Option<DateOrder>) -> IResult<&str, Option<(&str, Value)>> {
     let (input, key) = terminated(take_until("="), tag("=")).parse(input)?;
     match key {
         // parse these tags only
         "start" | "completed" | "due" => {
-            let (input, fragment) = date_time_fragment(input)?;
-            Ok((input, Some((key, fragment))))
+            let (input, fragment) = date_time_fragment_with(input, order)?;
+            Ok((input, Some((key, Value::Fragment(fragment)))))
+        }
+        "priority" => {
+            let (input, priority) = priority(input)?;
+            Ok((input, Some((key, Value::Priority(priority)))))
+        }
+        "tags" | "assignee" | "depends" => {
+            let (input, list) = take_till(|c| c == ';')(input)?;
+            let list = list
+                .split(',')
+                .map(|item| item.trim().trim_start_matches(['#', '@']))
+                .filter(|item| !item.is_empty())
+                .map(str::to_string)
+                .collect();
+            Ok((input, Some((key, Value::List(list)))))
+        }
+        "uid" => {
+            let (input, uid) = take_till1(|c| c == ';')(input)?;
+            Ok((input, Some((key, Value::Text(uid.to_string())))))
+        }
+        "estimate" | "spent" => {
+            let (input, duration) = duration(input)?;
+            Ok((input, Some((key, Value::Duration(duration)))))
+        }
+        "alarm" => {
+            let (input, alarms) =
+                separated_list1(tag(","), |input| alarm_spec(input, order)).parse(input)?;
+            Ok((input, Some((key, Value::Alarms(alarms)))))
+        }
+        // keep all other keys with their values
+        _ if is_extension_key(key) => {
+            let (input, value) = extension_value(input)?;
+            Ok((input, Some((key, Value::Extension(value)))))
         }
-        // ignore all other keys
-        _ => Ok((input, None)),
+        _ => Err(nom::Err::Error(nom::error::Error::new(
+            input,
+            nom::error::ErrorKind::Verify,
+        ))),
     }
 }
 
+/// Whether the key is not empty and without `;`, e.g. `ticket` or `jira.key`.
+fn is_extension_key(key: &str) -> bool {
+    !key.is_empty() && !key.contains(';')
+}
+
+/// Set the value of key, replacing the one of the same key if any.
+fn insert_extension(extensions: &mut Vec<(String, String)>, key: String, value: String) {
+    match extensions.iter_mut().find(|(k, _)| *k == key) {
+        Some((_, v)) => *v = value,
+        None => extensions.push((key, value)),
+    }
+}
+
+/// Push the name unless already in the list, case insensitive.
+pub(crate) fn push_unique(list: &mut Vec<String>, name: String) {
+    let lowercase = name.to_lowercase();
+    if !list.iter().any(|n| n.to_lowercase() == lowercase) {
+        list.push(name);
+    }
+}
+
+/// The value of extension, in double quotes if containing `;`, e.g. `"a;b"`,
+/// where `\"` and `\\` are a quote and a backslash.
+fn extension_value(input: &str) -> IResult<&str, String> {
+    let quoted = terminated(
+        delimited(
+            tag("\""),
+            opt(escaped_transform(none_of("\\\""), '\\', one_of("\\\""))),
+            tag("\""),
+        ),
+        peek(alt((semicolon, eof))),
+    );
+    alt((
+        quoted.map(Option::unwrap_or_default),
+        take_till(|c| c == ';').map(str::to_string),
+    ))
+    .parse(input)
+}
+
+/// The value of extension as written in link, quoted if it would not be read back as is.
+fn quote(value: &str) -> String {
+    if !value.contains(';') && !value.starts_with('"') {
+        return value.to_string();
+    }
+    let escaped = value.replace('\\', "\\\\").replace('"', "\\\"");
+    format!("\"{escaped}\"")
+}
+
+/// Alarm of optional `start` or `due` with duration, or a fragment.
+fn alarm_spec(input: &str, order: Option<DateOrder>) -> IResult<&str, AlarmSpec> {
+    let related = alt((
+        value(Related::Start, tag("start")),
+        value(Related::Due, tag("due")),
+    ));
+    alt((
+        (opt(related), duration).map(|(related, offset)| AlarmSpec::Relative { related, offset }),
+        (|input| date_time_fragment_with(input, order)).map(AlarmSpec::Absolute),
+    ))
+    .parse(input)
+}
+
+/// Priority of digit 1 to 9, or `high`, `medium` and `low` for 1, 5 and 9.
+fn priority(input: &str) -> IResult<&str, u8> {
+    alt((
+        one_of("123456789").map(|c: char| c as u8 - b'0'),
+        value(1, tag("high")),
+        value(5, tag("medium")),
+        value(9, tag("low")),
+    ))
+    .parse(input)
+}
+
 fn semicolon(input: &str) -> IResult<&str, &str> {
     tag(";")(input)
 }
 
-fn collect_fragments(
-    kvs: Vec<Option<(&str, DateTimeFragment)>>,
-) -> (
-    Option<DateTimeFragment>,
-    Option<DateTimeFragment>,
-    Option<DateTimeFragment>,
-) {
-    // extract date fragments
-    let mut start = None;
-    let mut due = None;
-    let mut completed = None;
-    for (k, v) in kvs
-        .into_iter()
-        // remove None items
-        .flatten()
-    {
-        match k {
-            "start" => {
-                start = Some(v);
-            }
-            "due" => {
-                due = Some(v);
-            }
-            "completed" => {
-                completed = Some(v);
-            }
-            _ => {}
+#[cfg(test)]
+#[allow(clippy::zero_prefixed_literal)]
+mod test {
+    use chrono::{NaiveDate, Weekday};
+    use proptest::{collection::vec, option, prelude::*};
+
+    use super::*;
+    use crate::{
+        convert::{Reference, due_to_datetime, fragment_to_datetime, of_second},
+        fragment::{
+            OffsetUnit,
+            test::{arb_fragment, arb_hour_rest, arb_year_rest},
+        },
+        parse::{ParseOptions, parse_text},
+    };
+
+    #[test]
+    fn priority_key() {
+        let (_, parsed) = link("2025-03-09;priority=2").unwrap();
+        assert_eq!(parsed.priority, Some(2));
+        let (_, parsed) = link("priority=high;due=2025-03-09").unwrap();
+        assert_eq!(parsed.priority, Some(1));
+        assert_eq!(parsed.due, Some(DateTimeFragment::from_ymd(2025, 03, 09)));
+        assert!(link("priority=0").is_err());
+        assert!(link("priority=urgent").is_err());
+    }
+
+    #[test]
+    fn month_or_day_key() {
+        let (_, parsed) = link("2025-03;due=10;priority=2").unwrap();
+        assert_eq!(parsed.due, Some(DateTimeFragment::from_m_d(10)));
+        assert_eq!(parsed.priority, Some(2));
+    }
+
+    #[test]
+    fn offset_keys() {
+        let (_, parsed) = link("2025-03-09;start=-1w;due=+3d").unwrap();
+        assert_eq!(
+            parsed.start,
+            Some(DateTimeFragment::from_offset(-1, OffsetUnit::Week))
+        );
+        assert_eq!(
+            parsed.due,
+            Some(DateTimeFragment::from_offset(3, OffsetUnit::Day))
+        );
+    }
+
+    #[test]
+    fn rfc3339_key() {
+        let (_, parsed) = link("due=2025-03-09T10:00:00.5Z;priority=1").unwrap();
+        let due = parsed.due.unwrap();
+        assert_eq!(due.nanosecond(), Some(500_000_000));
+        assert_eq!(due.offset(), chrono::FixedOffset::east_opt(0));
+        assert_eq!(parsed.priority, Some(1));
+    }
+
+    #[test]
+    fn locale_date() {
+        assert!(link("due=09/03/2025").is_err());
+        let (_, parsed) = link_with(
+            "09/03/2025..12/03/2025;alarm=08/03/2025T09",
+            Some(DateOrder::DayFirst),
+        )
+        .unwrap();
+        assert_eq!(parsed.base, Some(DateTimeFragment::from_ymd(2025, 03, 09)));
+        assert_eq!(parsed.due, Some(DateTimeFragment::from_ymd(2025, 03, 12)));
+        assert_eq!(
+            parsed.alarms,
+            vec![AlarmSpec::Absolute(DateTimeFragment::from_ymd_h(
+                2025, 03, 08, 09
+            ))]
+        );
+        let (_, parsed) = link_with("due=03/09/2025", Some(DateOrder::MonthFirst)).unwrap();
+        assert_eq!(parsed.due, Some(DateTimeFragment::from_ymd(2025, 03, 09)));
+    }
+
+    #[test]
+    fn basic_date() {
+        let (_, parsed) = link("20250309;due=20250312T1800").unwrap();
+        assert_eq!(parsed.base, Some(DateTimeFragment::from_ymd(2025, 03, 09)));
+        assert_eq!(
+            parsed.due,
+            Some(DateTimeFragment::from_ymd_hm(2025, 03, 12, 18, 00))
+        );
+    }
+
+    #[test]
+    fn range() {
+        let (_, parsed) = link("2025-03-09..2025-03-12;priority=2").unwrap();
+        assert_eq!(parsed.base, Some(DateTimeFragment::from_ymd(2025, 03, 09)));
+        assert_eq!(parsed.due, Some(DateTimeFragment::from_ymd(2025, 03, 12)));
+        assert_eq!(parsed.priority, Some(2));
+        // a day over the base with a day, as `due=12` reads
+        let (_, parsed) = link("2025-03-09..12").unwrap();
+        assert_eq!(parsed.due, Some(DateTimeFragment::from_m_d(12)));
+        let (_, parsed) = link("2025-03..05").unwrap();
+        assert_eq!(parsed.due, Some(DateTimeFragment::from_m(05)));
+        // the hour over the base with an hour
+        let (_, parsed) = link("2025-03-09T10..12").unwrap();
+        assert_eq!(parsed.due, Some(DateTimeFragment::from_h(12)));
+        let (_, parsed) = link("2025-03-09T10:30..12").unwrap();
+        assert_eq!(parsed.due, Some(DateTimeFragment::from_h(12)));
+        assert_eq!(
+            due_to_datetime(&parsed.due, &parsed.start, &parsed.base, &reference()),
+            of_second(2025, 03, 09, 12, 31, 00)
+        );
+        let (_, parsed) = link("2025-03-09T10..T12:30").unwrap();
+        assert_eq!(parsed.due, Some(DateTimeFragment::from_hm(12, 30)));
+        // due key overrides the end of range
+        let (_, parsed) = link("2025-03-09..12;due=14").unwrap();
+        assert_eq!(parsed.due, Some(DateTimeFragment::from_m_d(14)));
+        assert!(link("2025-03-09..").is_err());
+        assert!(link("..2025-03-09").is_err());
+    }
+
+    #[test]
+    fn tags_key() {
+        let (_, parsed) = link("2025-03-09;tags=ops, #review,;due=10").unwrap();
+        assert_eq!(parsed.tags, ["ops", "review"]);
+        let defaults = link("tags=hiring").unwrap().1;
+        assert_eq!(
+            parsed.with_defaults(&defaults).tags,
+            ["hiring", "ops", "review"]
+        );
+        let parsed = link("tags=ops").unwrap().1;
+        assert_eq!(parsed.with_defaults(&defaults).tags, ["hiring", "ops"]);
+    }
+
+    #[test]
+    fn assignee_key() {
+        let (_, parsed) = link("assignee=@alice,bob").unwrap();
+        assert_eq!(parsed.assignees, ["alice", "bob"]);
+        let defaults = link("2025-03-09;assignee=carol").unwrap().1;
+        assert_eq!(parsed.with_defaults(&defaults).assignees, ["alice", "bob"]);
+        let parsed = link("due=10").unwrap().1;
+        assert_eq!(parsed.with_defaults(&defaults).assignees, ["carol"]);
+    }
+
+    #[test]
+    fn uid_key() {
+        let (_, parsed) = link("2025-03-09;uid=k3x9a").unwrap();
+        assert_eq!(parsed.uid.as_deref(), Some("k3x9a"));
+        let defaults = parsed.clone();
+        assert_eq!(link("").unwrap().1.with_defaults(&defaults).uid, None);
+        assert!(link("uid=").is_err());
+    }
+
+    #[test]
+    fn depends_key() {
+        let (_, parsed) = link("2025-03-09;depends=a1, b2").unwrap();
+        assert_eq!(parsed.depends, ["a1", "b2"]);
+        assert!(
+            link("")
+                .unwrap()
+                .1
+                .with_defaults(&parsed)
+                .depends
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn alarm_key() {
+        let (_, parsed) = link("2025-03-09;alarm=-PT15M,start-PT1H,2025-03-08T20").unwrap();
+        assert_eq!(
+            parsed.alarms,
+            [
+                AlarmSpec::Relative {
+                    related: None,
+                    offset: TimeDelta::minutes(-15)
+                },
+                AlarmSpec::Relative {
+                    related: Some(Related::Start),
+                    offset: TimeDelta::hours(-1)
+                },
+                AlarmSpec::Absolute(DateTimeFragment::from_ymd_h(2025, 03, 08, 20)),
+            ]
+        );
+        assert!(link("alarm=soon").is_err());
+    }
+
+    #[test]
+    fn estimate_and_spent_keys() {
+        let (_, parsed) = link("2025-03-09;estimate=PT2H;spent=PT1H30M").unwrap();
+        assert_eq!(parsed.estimate, Some(TimeDelta::hours(2)));
+        assert_eq!(parsed.spent, Some(TimeDelta::minutes(90)));
+        assert!(link("estimate=2h").is_err());
+        // out of the range of duration
+        assert!(link("estimate=P99999999999999W").is_err());
+        assert!(link("2025-03-09;spent=P999999999999D").is_err());
+    }
+
+    #[test]
+    fn unknown_key() {
+        let (_, parsed) = link("2025-03-09;note=later;due=2025-03-12;url=").unwrap();
+        assert_eq!(parsed.due, Some(DateTimeFragment::from_ymd(2025, 03, 12)));
+        assert_eq!(
+            parsed.extensions,
+            [
+                ("note".to_string(), "later".to_string()),
+                ("url".to_string(), String::new())
+            ]
+        );
+        let (_, parsed) = link("ticket=OPS-1;ticket=OPS-2").unwrap();
+        assert_eq!(
+            parsed.extensions,
+            [("ticket".to_string(), "OPS-2".to_string())]
+        );
+        let (_, parsed) = link("jira.key=OPS-1;to do=later").unwrap();
+        assert_eq!(
+            parsed.extensions,
+            [
+                ("jira.key".to_string(), "OPS-1".to_string()),
+                ("to do".to_string(), "later".to_string())
+            ]
+        );
+        assert!(link("=later").is_err());
+        assert!(link("note;due=later").is_err());
+    }
+
+    #[test]
+    fn quoted_value() {
+        let (_, parsed) = link(r#"url="a?b=1;c=2";note="say \"hi\" \\o/""#).unwrap();
+        assert_eq!(
+            parsed.extensions,
+            [
+                ("url".to_string(), "a?b=1;c=2".to_string()),
+                ("note".to_string(), r#"say "hi" \o/"#.to_string())
+            ]
+        );
+        assert_eq!(parsed.to_string(), r#"url="a?b=1;c=2";note=say "hi" \o/"#);
+        // a quote not closing the value is kept as is
+        let (_, parsed) = link(r#"note="a"b;due=12"#).unwrap();
+        assert_eq!(parsed.extensions[0].1, r#""a"b"#);
+        assert_eq!(parsed.to_string(), r#"due=12;note="\"a\"b""#);
+        assert!(link(r#"note="a;b"#).is_err());
+    }
+
+    #[test]
+    fn inherit_extensions() {
+        let defaults = link("2025-03;project=acme;ticket=OPS-1").unwrap().1;
+        let parsed = link("due=12;ticket=OPS-2;url=x").unwrap().1;
+        assert_eq!(
+            parsed.with_defaults(&defaults).extensions,
+            [
+                ("project".to_string(), "acme".to_string()),
+                ("ticket".to_string(), "OPS-2".to_string()),
+                ("url".to_string(), "x".to_string())
+            ]
+        );
+        let parsed = link("2025-03-09;ticket=OPS-3").unwrap().1;
+        assert_eq!(
+            parsed.with_defaults(&defaults).extensions,
+            [
+                ("project".to_string(), "acme".to_string()),
+                ("ticket".to_string(), "OPS-3".to_string())
+            ]
+        );
+    }
+
+    #[test]
+    fn inherit_tags() {
+        let defaults = link("2025-03;tags=ops,review").unwrap().1;
+        let parsed = link("tags=Ops,hiring").unwrap().1;
+        assert_eq!(
+            parsed.with_defaults(&defaults).tags,
+            ["ops", "review", "hiring"]
+        );
+    }
+
+    #[test]
+    fn inherit_priority() {
+        let defaults = link("2025-03-09;priority=3").unwrap().1;
+        assert_eq!(
+            link("").unwrap().1.with_defaults(&defaults).priority,
+            Some(3)
+        );
+        assert_eq!(
+            link("priority=7")
+                .unwrap()
+                .1
+                .with_defaults(&defaults)
+                .priority,
+            Some(7)
+        );
+        assert_eq!(
+            link("2025-03-12")
+                .unwrap()
+                .1
+                .with_defaults(&defaults)
+                .priority,
+            Some(3)
+        );
+    }
+
+    #[test]
+    fn display() {
+        let text = "2025-03-09T10:30;start=T09;due=12;completed=2025-03-12T18:00:00.5Z;\
+            priority=2;tags=ops,review;assignee=alice;uid=k3x9a;depends=a1,b2;\
+            alarm=-PT15M,startPT1H,08T20;estimate=PT2H;spent=PT1H30M";
+        assert_eq!(link(text).unwrap().1.to_string(), text);
+        let (_, parsed) = link("priority=high;tags=#ops;due=2025-03-09").unwrap();
+        assert_eq!(parsed.to_string(), "due=2025-03-09;priority=1;tags=ops");
+        assert_eq!(Link::default().to_string(), "");
+    }
+
+    #[test]
+    fn canonical() {
+        let canonical = |text| link(text).unwrap().1.canonical().to_string();
+        assert_eq!(
+            canonical("2025-03-09;start=2025-03-09;due=2025-03-12"),
+            "2025-03-09..12"
+        );
+        assert_eq!(canonical("2025-03-09..2025-03-12"), "2025-03-09..12");
+        assert_eq!(
+            canonical("20250309;start=2025-03-09;due=2025-03-12T18:00"),
+            "2025-03-09..12T18:00"
+        );
+        assert_eq!(canonical("2025-03-09;due=12"), "2025-03-09..12");
+        assert_eq!(
+            canonical("2025-03-09;due=2025-04-12T18:00"),
+            "2025-03-09..04-12T18:00"
+        );
+        assert_eq!(
+            canonical("2025-03-09T10;due=2025-03-09T12:30"),
+            "2025-03-09T10..T12:30"
+        );
+        assert_eq!(canonical("2025;due=2025-05"), "2025..05");
+        assert_eq!(canonical("2025-03-09T10;due=T12"), "2025-03-09T10..12");
+        assert_eq!(
+            canonical("2025-03-09T10;due=2025-03-12T10"),
+            "2025-03-09T10..12T10"
+        );
+        assert_eq!(canonical("2025-03;due=2025-05"), "2025-03..05");
+        // a bare day over a month is a month as the end of range
+        assert_eq!(canonical("2025-03;due=2025-03-05"), "2025-03;due=05");
+        assert_eq!(
+            canonical("2025-03-09;start=2025-03-08;due=2025-03-12"),
+            "2025-03-09;start=08;due=12"
+        );
+        assert_eq!(canonical("20250309;due=2025-03-09"), "2025-03-09");
+        // completed is kept even if the same as the base
+        assert_eq!(
+            canonical("2025-03-09;completed=2025-03-09;alarm=2025-03-08T20"),
+            "2025-03-09;completed=09;alarm=08T20"
+        );
+        assert_eq!(canonical("2025-W11-3;due=2025-03-14"), "2025-W11-3..14");
+        // the base of anchor depends on today
+        assert_eq!(canonical("fri;due=2025-03-14"), "fri..2025-03-14");
+        assert_eq!(canonical("due=2025-03-14"), "due=2025-03-14");
+    }
+
+    #[test]
+    fn from_attributes() {
+        let cases = [
+            ("2025-03-09..12", "2025-03-09..12"),
+            (
+                "2025-03-09T10:30;due=T12:00:00",
+                "2025-03-09T10:30..T12:00:00",
+            ),
+            // a bare day over an hour is an hour as the end of range
+            ("2025-03-09T10:30;due=10", "2025-03-09T10:30;due=10"),
+            (
+                "2025-03-09T10:30;due=2025-03-11T00:00:00",
+                "2025-03-10;start=09T10:30",
+            ),
+            (
+                "due=2025-03-12;alarm=-PT15M",
+                "due=2025-03-12;alarm=due-PT15M",
+            ),
+            (
+                "2025-03;priority=2;estimate=PT2H",
+                "2025-03;priority=2;estimate=PT2H",
+            ),
+            ("2025", "2025"),
+            ("2025-03..05", "2025-03..05"),
+            // missing fields of end are taken from the base
+            ("2025-03-09..2025-04", "2025-03-09..04-09"),
+        ];
+        for (text, expected) in cases {
+            let mut todos = vec![];
+            let markdown = format!("- [ ] a <agmd:{text}>\n");
+            parse_text(&mut todos, "test.md", &markdown, &ParseOptions::default());
+            let attributes = todos[0].attributes.as_ref().unwrap();
+            assert_eq!(Link::from_attributes(attributes).to_string(), expected);
+        }
+    }
+
+    #[test]
+    fn from_attributes_exact() {
+        let start = of_second(2025, 03, 09, 10, 30, 15).unwrap();
+        let attributes = Attributes {
+            start: Some(start),
+            due: Some(start + TimeDelta::milliseconds(1500)),
+            status: agemda_core::Status::NeedsAction,
+            completed: None,
+            priority: None,
+            alarms: vec![],
+            estimate: None,
+            spent: None,
+            extensions: vec![],
+        };
+        assert_eq!(
+            Link::from_attributes(&attributes).to_string(),
+            "2025-03-09T10:30:15..T10:30:16.5"
+        );
+    }
+
+    /// Wednesday 2025-03-12 with weeks starting on Monday.
+    fn reference() -> Reference {
+        Reference {
+            today: NaiveDate::from_ymd_opt(2025, 03, 12).unwrap(),
+            week_start: Weekday::Mon,
+        }
+    }
+
+    fn arb_list() -> impl Strategy<Value = Vec<String>> {
+        vec("[a-z][a-z0-9_-]{0,8}", 0..3)
+    }
+
+    fn arb_alarm(fragment: BoxedStrategy<DateTimeFragment>) -> impl Strategy<Value = AlarmSpec> {
+        let related = option::of(prop_oneof![Just(Related::Start), Just(Related::Due)]);
+        prop_oneof![
+            (related, -1_000_000..1_000_000i64).prop_map(|(related, seconds)| {
+                AlarmSpec::Relative {
+                    related,
+                    offset: TimeDelta::seconds(seconds),
+                }
+            }),
+            fragment.prop_map(AlarmSpec::Absolute),
+        ]
+    }
+
+    /// Links of the fragments, with other keys of any values the parser accepts.
+    fn arb_link(fragment: BoxedStrategy<DateTimeFragment>) -> impl Strategy<Value = Link> {
+        let duration = option::of((-1_000_000..1_000_000i64).prop_map(TimeDelta::seconds));
+        (
+            (
+                option::of(fragment.clone()),
+                option::of(fragment.clone()),
+                option::of(fragment.clone()),
+                option::of(fragment.clone()),
+            ),
+            (option::of(1..=9u8), arb_list(), arb_list()),
+            (option::of("[a-z0-9]{1,8}"), arb_list()),
+            (vec(arb_alarm(fragment), 0..3), duration.clone(), duration),
+            vec(("x-[a-z0-9_. -]{0,8}", "[ -~]{0,10}"), 0..3),
+        )
+            .prop_map(
+                |(
+                    (base, start, due, completed),
+                    (priority, tags, assignees),
+                    (uid, depends),
+                    (alarms, estimate, spent),
+                    extensions,
+                )| Link {
+                    base,
+                    start,
+                    due,
+                    completed,
+                    priority,
+                    tags,
+                    assignees,
+                    uid,
+                    depends,
+                    alarms,
+                    estimate,
+                    spent,
+                    extensions: extensions.into_iter().fold(
+                        vec![],
+                        |mut extensions, (key, value)| {
+                            insert_extension(&mut extensions, key, value);
+                            extensions
+                        },
+                    ),
+                },
+            )
+    }
+
+    /// Fragments mostly of calendar dates, which resolve.
+    fn arb_calendar_fragment() -> BoxedStrategy<DateTimeFragment> {
+        prop_oneof![
+            4 => arb_year_rest(true).prop_map(DateTimeFragment::YearRest),
+            1 => (1..29u32).prop_map(DateTimeFragment::MonthOrDay),
+            1 => option::of(arb_hour_rest(true)).prop_map(DateTimeFragment::HourRest),
+            1 => arb_fragment(),
+        ]
+        .boxed()
+    }
+
+    proptest! {
+        #[test]
+        fn round_trip(parsed in arb_link(arb_fragment().boxed())) {
+            let text = parsed.to_string();
+            prop_assert_eq!(link(&text), Ok(("", parsed)));
+        }
+
+        #[test]
+        fn canonical_resolves_the_same(parsed in arb_link(arb_calendar_fragment())) {
+            let reference = &reference();
+            let resolve = |link: &Link| {
+                let alarms: Vec<_> = link
+                    .alarms
+                    .iter()
+                    .map(|alarm| match alarm {
+                        AlarmSpec::Absolute(fragment) => fragment_to_datetime(
+                            &Some(fragment.clone()),
+                            &link.base,
+                            Role::Start,
+                            reference,
+                        ),
+                        AlarmSpec::Relative { .. } => None,
+                    })
+                    .collect();
+                (
+                    fragment_to_datetime(&link.start, &link.base, Role::Start, reference),
+                    due_to_datetime(&link.due, &link.start, &link.base, reference),
+                    link.completed.as_ref().map(|_| {
+                        fragment_to_datetime(&link.completed, &link.base, Role::End, reference)
+                    }),
+                    alarms,
+                )
+            };
+            let canonical = parsed.clone().canonical();
+            prop_assert_eq!(resolve(&canonical), resolve(&parsed));
+            prop_assert_eq!(canonical.clone().canonical(), canonical.clone());
+            let text = canonical.to_string();
+            prop_assert_eq!(link(&text), Ok(("", canonical)));
         }
     }
-    (start, due, completed)
 }
@@ -1,9 +1,9 @@
 use std::path::{Path, PathBuf};
 
-use agemda_core::Todo;
+use agemda_core::{Todo, graph::resolve_dependencies};
 use ignore::{WalkBuilder, types::TypesBuilder};
 
-use crate::parse::parse_file;
+use crate::parse::{ParseOptions, parse_file};
 
 pub fn walk_markdown_files(
     root: impl AsRef<Path>,
@@ -31,14 +31,18 @@ pub fn walk_markdown_files(
     })
 }
 
-pub fn load_todos_from_root(root: impl AsRef<Path>) -> anyhow::Result<Vec<Todo>> {
+pub fn load_todos_from_root(
+    root: impl AsRef<Path>,
+    options: &ParseOptions,
+) -> anyhow::Result<Vec<Todo>> {
     let root = root.as_ref();
     let mut todos = vec![];
 
     for path in walk_markdown_files(root) {
         let path = path?;
-        parse_file(&mut todos, path)?;
+        parse_file(&mut todos, path, options)?;
     }
+    resolve_dependencies(&mut todos);
 
     Ok(todos)
 }
@@ -1,164 +1,1311 @@
 use std::{
+    borrow::Cow,
+    collections::BTreeMap,
     fs,
+    ops::Range,
     path::{Path, PathBuf},
 };
 
-use agemda_core::{Attributes, Metadata, Todo};
+use agemda_core::{
+    Alarm, Attributes, Description, Metadata, Related, SpanStyle, Status, Summary, Todo, Trigger,
+};
 use anyhow::Context;
-use pulldown_cmark::{Event, Options, Parser, Tag, TagEnd};
+use chrono::{DateTime, Local};
+use pulldown_cmark::{Event, HeadingLevel, MetadataBlockKind, Options, Parser, Tag, TagEnd};
 
 use crate::{
-    convert::{Role, fragment_to_datetime},
-    link::link,
+    clock::{clocks, is_clock_block},
+    convert::{Reference, Role, due_to_datetime, fragment_to_datetime},
+    fragment::{DateOrder, DateTimeFragment},
+    front_matter::parse_front_matter,
+    link::{AlarmSpec, Link, link_with, push_unique},
+    pattern::PathPattern,
 };
 
-pub fn parse_file(acc: &mut Vec<Todo>, path: impl AsRef<Path>) -> anyhow::Result<()> {
+/// Options for parsing markdown files.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParseOptions {
+    /// The front matter key whose value is the agmd link of the whole file.
+    pub front_matter_key: String,
+    /// Patterns of path to infer the base of the file, the first match is used.
+    pub path_patterns: Vec<PathPattern>,
+    /// The date and week start resolving named anchors like `fri` and `eow`.
+    pub reference: Reference,
+    /// The order of locale dates like `09/03/2025`, which are rejected if not set.
+    pub date_order: Option<DateOrder>,
+}
+
+impl Default for ParseOptions {
+    fn default() -> Self {
+        Self {
+            front_matter_key: "agmd".to_string(),
+            path_patterns: vec![],
+            reference: Reference::default(),
+            date_order: None,
+        }
+    }
+}
+
+pub fn parse_file(
+    acc: &mut Vec<Todo>,
+    path: impl AsRef<Path>,
+    options: &ParseOptions,
+) -> anyhow::Result<()> {
     let path = path.as_ref();
     let text = fs::read_to_string(path)
         .with_context(|| format!("fail to read path {}", path.display()))?;
+    parse_text(acc, path, &text, options);
+    Ok(())
+}
 
-    // create parser
-    let mut options = Options::empty();
-    options.insert(Options::ENABLE_TASKLISTS);
-    let parser = Parser::new_ext(&text, options);
+pub fn parse_text(acc: &mut Vec<Todo>, path: impl AsRef<Path>, text: &str, options: &ParseOptions) {
+    // custom markers are read back from `text` by the range of marker
+    let masked = mask_task_markers(text);
+    let parser = markdown_parser(&masked);
 
     // states
-    let mut state = State::new(path);
+    let mut state = State::new(path.as_ref(), text, options);
 
     // handle events
-    for event in parser {
-        if let Some(todo) = state.handle(&event) {
-            acc.push(todo);
-        }
+    for (event, range) in parser.into_offset_iter() {
+        state.handle(&event, range);
     }
 
-    Ok(())
+    state.finish(acc);
 }
 
 #[derive(Debug, Clone, PartialEq)]
-struct State {
+struct State<'a> {
     path: PathBuf,
-    hier: Vec<ListState>,
+    /// The source text of the file.
+    text: &'a str,
+    options: &'a ParseOptions,
+    hier: Vec<ItemState>,
     in_agmd_link: bool,
+    /// The inline style at current position.
+    style: SpanStyle,
+    /// Text of the front matter, collected while inside it.
+    front_matter_text: Option<String>,
+    front_matter: BTreeMap<String, String>,
+    /// The link inherited by every task of the file.
+    defaults: Link,
+    /// The link inferred from path, inherited by the front matter link.
+    path_defaults: Link,
+    /// The heading being parsed.
+    heading: Option<HeadingState>,
+    /// The enclosing headings, with the link inherited by tasks under them.
+    headings: Vec<(HeadingState, Link)>,
+    /// Each task of the file in order, with the slot of its parent task
+    /// and the todo if it is one.
+    slots: Vec<(Option<usize>, Option<Todo>)>,
 }
 
 #[derive(Debug, Clone, PartialEq)]
-enum ListState {
-    Plain,
-    Task(bool, String, Option<String>),
+struct HeadingState {
+    level: HeadingLevel,
+    text: String,
+    agmd: Option<String>,
 }
 
-impl State {
-    fn new(path: &Path) -> Self {
+#[derive(Debug, Clone, PartialEq)]
+struct ItemState {
+    /// The source range of the item.
+    range: Range<usize>,
+    /// Number of open blocks directly in the item.
+    blocks: usize,
+    /// Whether the first block, which is the summary of task, has ended.
+    is_first_block_done: bool,
+    /// Whether any task is nested in the item.
+    has_nested_task: bool,
+    task: Option<TaskState>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct TaskState {
+    status: Status,
+    summary: Summary,
+    agmd: Option<String>,
+    /// The position of the task in `State::slots`.
+    slot: usize,
+    /// Source ranges of the nested content other than tasks.
+    description: Vec<Range<usize>>,
+    /// Source range of the marker, e.g. `[ ]`.
+    marker: Range<usize>,
+    /// Source range of the own link, e.g. `<agmd:2025-03-09>`.
+    link: Option<Range<usize>>,
+    /// Source offset of the end of summary.
+    summary_end: usize,
+}
+
+impl<'a> State<'a> {
+    fn new(path: &Path, text: &'a str, options: &'a ParseOptions) -> Self {
+        let path_defaults = Link {
+            base: options
+                .path_patterns
+                .iter()
+                .find_map(|pattern| pattern.base_of(path)),
+            ..Default::default()
+        };
         Self {
             path: path.to_path_buf(),
+            text,
+            options,
             hier: vec![],
             in_agmd_link: false,
+            style: SpanStyle::default(),
+            front_matter_text: None,
+            front_matter: BTreeMap::new(),
+            defaults: path_defaults.clone(),
+            path_defaults,
+            heading: None,
+            headings: vec![],
+            slots: vec![],
+        }
+    }
+
+    /// The link inherited by tasks at current position.
+    fn inherited(&self) -> &Link {
+        match self.headings.last() {
+            Some((_, link)) => link,
+            None => &self.defaults,
         }
     }
 
-    fn handle(&mut self, event: &Event) -> Option<Todo> {
-        self.handle_item_start(event);
-        self.handle_task(event);
-        self.handle_link(event);
-        self.handle_text(event);
-        self.handle_item_end(event)
+    /// The link inherited from enclosing tasks, if any of them has a link.
+    fn inherited_from_tasks(&self) -> Option<Link> {
+        self.hier
+            .iter()
+            .filter_map(|item| item.task.as_ref()?.agmd.as_ref())
+            .fold(None, |acc: Option<Link>, agmd| {
+                let inherited = acc.unwrap_or_else(|| self.inherited().clone());
+                // malformed link of parent is ignored
+                Some(match link_with(agmd, self.options.date_order) {
+                    Ok((_, link)) => link.with_defaults(&inherited),
+                    Err(_) => inherited,
+                })
+            })
+    }
+
+    fn handle(&mut self, event: &Event, range: Range<usize>) {
+        self.handle_front_matter(event);
+        self.handle_heading(event);
+        self.handle_item_start(event, &range);
+        self.handle_task(event, &range);
+        self.handle_block(event, &range);
+        self.handle_link(event, &range);
+        self.handle_style(event);
+        self.handle_text(event, &range);
+        self.handle_item_end(event);
     }
 
-    fn handle_item_start(&mut self, event: &Event) {
+    /// Move the todos of the file into `acc`, linking parents and children.
+    fn finish(self, acc: &mut Vec<Todo>) {
+        // the index in acc of each slot
+        let mut indices = vec![None; self.slots.len()];
+        let mut next = acc.len();
+        for (index, (_, todo)) in indices.iter_mut().zip(&self.slots) {
+            if todo.is_some() {
+                *index = Some(next);
+                next += 1;
+            }
+        }
+
+        let parent_slots: Vec<_> = self.slots.iter().map(|(parent, _)| *parent).collect();
+        for (parent_slot, todo) in self.slots {
+            let Some(mut todo) = todo else {
+                continue;
+            };
+            // the nearest ancestor which is a todo
+            let mut parent_slot = parent_slot;
+            while let Some(slot) = parent_slot
+                && indices[slot].is_none()
+            {
+                parent_slot = parent_slots[slot];
+            }
+            if let Some(parent) = parent_slot.and_then(|slot| indices[slot]) {
+                todo.parent = Some(parent);
+                todo.depth = acc[parent].depth + 1;
+                let index = acc.len();
+                acc[parent].children.push(index);
+            }
+            acc.push(todo);
+        }
+    }
+
+    fn handle_front_matter(&mut self, event: &Event) {
         match event {
-            Event::Start(Tag::Item) => {
-                self.hier.push(ListState::Plain);
+            Event::Start(Tag::MetadataBlock(MetadataBlockKind::YamlStyle)) => {
+                self.front_matter_text = Some(String::new());
+            }
+            Event::Text(cow_str) => {
+                if let Some(text) = &mut self.front_matter_text {
+                    text.push_str(cow_str);
+                }
+            }
+            Event::End(TagEnd::MetadataBlock(MetadataBlockKind::YamlStyle)) => {
+                let text = self.front_matter_text.take().unwrap_or_default();
+                self.front_matter = parse_front_matter(&text);
+                // malformed front matter link is ignored
+                if let Some(Ok((_, defaults))) = self
+                    .front_matter
+                    .get(&self.options.front_matter_key)
+                    .map(|value| link_with(value, self.options.date_order))
+                {
+                    self.defaults = defaults.with_defaults(&self.path_defaults);
+                }
             }
             _ => {}
         }
     }
 
-    fn handle_task(&mut self, event: &Event) {
+    fn handle_heading(&mut self, event: &Event) {
         match event {
-            Event::TaskListMarker(b) => {
-                if let Some(list_state) = self.hier.last_mut() {
-                    *list_state = ListState::Task(*b, String::new(), None);
+            Event::Start(Tag::Heading { level, .. }) => {
+                // leave headings of the same or lower level
+                while self
+                    .headings
+                    .last()
+                    .is_some_and(|(heading, _)| heading.level >= *level)
+                {
+                    self.headings.pop();
+                }
+                self.heading = Some(HeadingState {
+                    level: *level,
+                    text: String::new(),
+                    agmd: None,
+                });
+            }
+            Event::End(TagEnd::Heading(_)) => {
+                if let Some(mut heading) = self.heading.take() {
+                    let inherited = self.inherited();
+                    // malformed heading link is ignored
+                    let link = match heading
+                        .agmd
+                        .as_deref()
+                        .map(|agmd| link_with(agmd, self.options.date_order))
+                    {
+                        Some(Ok((_, link))) => link.with_defaults(inherited),
+                        _ => inherited.clone(),
+                    };
+                    heading.text = heading.text.trim().to_string();
+                    self.headings.push((heading, link));
                 }
             }
             _ => {}
         }
     }
 
-    fn handle_link(&mut self, event: &Event) {
-        if let Some(last) = self.hier.last_mut() {
-            match last {
-                ListState::Task(_, _, agmd) => match event {
-                    Event::Start(Tag::Link { dest_url, .. }) => {
-                        if dest_url.starts_with("agmd:") {
-                            *agmd = Some(dest_url[5..].to_string());
-                            self.in_agmd_link = true;
+    fn handle_item_start(&mut self, event: &Event, range: &Range<usize>) {
+        if let Event::Start(Tag::Item) = event {
+            self.hier.push(ItemState {
+                range: range.clone(),
+                blocks: 0,
+                is_first_block_done: false,
+                has_nested_task: false,
+                task: None,
+            });
+        }
+    }
+
+    #[allow(clippy::single_match)]
+    fn handle_task(&mut self, event: &Event, range: &Range<usize>) {
+        match event {
+            Event::TaskListMarker(_) => {
+                if let Some((item, ancestors)) = self.hier.split_last_mut() {
+                    for ancestor in ancestors.iter_mut() {
+                        ancestor.has_nested_task = true;
+                    }
+                    let parent = ancestors
+                        .iter()
+                        .rev()
+                        .find_map(|ancestor| Some(ancestor.task.as_ref()?.slot));
+                    let status = self.text[range.start..]
+                        .chars()
+                        .nth(1)
+                        .and_then(Status::from_marker)
+                        .unwrap_or_default();
+                    item.task = Some(TaskState {
+                        status,
+                        summary: Summary::default(),
+                        agmd: None,
+                        slot: self.slots.len(),
+                        description: vec![],
+                        marker: range.clone(),
+                        link: None,
+                        summary_end: range.end,
+                    });
+                    self.slots.push((parent, None));
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Track blocks directly in the item, collecting description of task.
+    fn handle_block(&mut self, event: &Event, range: &Range<usize>) {
+        let Some(item) = self.hier.last_mut() else {
+            return;
+        };
+        match event {
+            Event::Start(tag) if is_block(tag) => {
+                if item.blocks == 0 {
+                    // the first paragraph is the summary
+                    let is_summary = !item.is_first_block_done && matches!(tag, Tag::Paragraph);
+                    if !is_summary {
+                        item.is_first_block_done = true;
+                        // items of nested list are collected one by one
+                        if !matches!(tag, Tag::List(_))
+                            && let Some(task) = &mut item.task
+                        {
+                            task.description.push(range.clone());
                         }
                     }
-                    Event::End(TagEnd::Link) => self.in_agmd_link = false,
-                    _ => {}
-                },
-                _ => {}
+                }
+                item.blocks += 1;
+            }
+            Event::End(tag_end) if is_block_end(tag_end) => {
+                item.blocks = item.blocks.saturating_sub(1);
+                if item.blocks == 0 {
+                    item.is_first_block_done = true;
+                }
             }
+            _ => {}
         }
     }
 
-    fn handle_text(&mut self, event: &Event) {
-        if !self.in_agmd_link {
-            if let Some(last) = self.hier.last_mut() {
-                match last {
-                    ListState::Task(_, text, _) => match event {
-                        Event::Text(cow_str) => text.push_str(&cow_str),
-                        _ => {}
-                    },
-                    ListState::Plain => {}
+    /// The task at current position, while in its summary.
+    fn summary_task(&mut self) -> Option<&mut TaskState> {
+        match self.hier.last_mut() {
+            Some(item) if !item.is_first_block_done => item.task.as_mut(),
+            _ => None,
+        }
+    }
+
+    fn handle_link(&mut self, event: &Event, range: &Range<usize>) {
+        let (agmd, link_range) = match self.heading {
+            Some(HeadingState { ref mut agmd, .. }) => (Some(agmd), None),
+            None => match self.summary_task() {
+                Some(task) => (Some(&mut task.agmd), Some(&mut task.link)),
+                None => (None, None),
+            },
+        };
+        if let Some(agmd) = agmd {
+            match event {
+                Event::Start(Tag::Link { dest_url, .. }) => {
+                    if let Some(rest) = dest_url.strip_prefix("agmd:") {
+                        *agmd = Some(rest.to_string());
+                        if let Some(link_range) = link_range {
+                            *link_range = Some(range.clone());
+                        }
+                        self.in_agmd_link = true;
+                    }
                 }
+                Event::End(TagEnd::Link) => self.in_agmd_link = false,
+                _ => {}
             }
         }
     }
 
-    fn handle_item_end(&mut self, event: &Event) -> Option<Todo> {
+    fn handle_style(&mut self, event: &Event) {
+        let (style, value) = match event {
+            Event::Start(Tag::Emphasis) => (&mut self.style.emphasis, true),
+            Event::End(TagEnd::Emphasis) => (&mut self.style.emphasis, false),
+            Event::Start(Tag::Strong) => (&mut self.style.strong, true),
+            Event::End(TagEnd::Strong) => (&mut self.style.strong, false),
+            Event::Start(Tag::Strikethrough) => (&mut self.style.strikethrough, true),
+            Event::End(TagEnd::Strikethrough) => (&mut self.style.strikethrough, false),
+            Event::Start(Tag::Link { dest_url, .. }) if !dest_url.starts_with("agmd:") => {
+                (&mut self.style.link, true)
+            }
+            Event::End(TagEnd::Link) => (&mut self.style.link, false),
+            _ => return,
+        };
+        *style = value;
+    }
+
+    fn handle_text(&mut self, event: &Event, range: &Range<usize>) {
+        let style = self.style;
         match event {
-            Event::End(TagEnd::Item) => {
-                let last = self.hier.pop()?;
-                match last {
-                    ListState::Task(b, summary, Some(agmd)) => {
-                        let attributes = match link(&agmd) {
-                            Ok((_, link)) => {
-                                let start =
-                                    fragment_to_datetime(&link.start, &link.base, Role::Start);
-                                let due = fragment_to_datetime(&link.due, &link.base, Role::End);
-                                let completed = match b {
-                                    true => match &link.completed {
-                                        Some(_) => fragment_to_datetime(
-                                            &link.completed,
-                                            &link.base,
-                                            Role::End,
-                                        ),
-                                        None => due,
-                                    },
-                                    false => None,
-                                };
-                                Ok(Attributes {
-                                    start,
-                                    due,
-                                    completed,
-                                })
-                            }
-                            Err(_) => Err(agmd),
-                        };
-                        Some(Todo {
-                            metadata: Metadata {
-                                path: self.path.clone(),
-                            },
-                            summary,
-                            attributes,
-                        })
-                    }
-                    _ => None,
+            Event::Text(_)
+            | Event::Code(_)
+            | Event::InlineHtml(_)
+            | Event::End(
+                TagEnd::Emphasis | TagEnd::Strong | TagEnd::Strikethrough | TagEnd::Link,
+            ) => {
+                if let Some(task) = self.summary_task() {
+                    task.summary_end = task.summary_end.max(range.end);
                 }
             }
-            _ => None,
+            _ => {}
+        }
+        if self.in_agmd_link {
+            return;
+        }
+        if let Some(heading) = &mut self.heading {
+            if let Event::Text(cow_str) | Event::Code(cow_str) = event {
+                heading.text.push_str(cow_str);
+            }
+            return;
         }
+        if let Some(task) = self.summary_task() {
+            match event {
+                Event::Text(cow_str) => task.summary.push(cow_str, style),
+                Event::Code(cow_str) => task.summary.push(
+                    cow_str,
+                    SpanStyle {
+                        code: true,
+                        ..style
+                    },
+                ),
+                Event::SoftBreak | Event::HardBreak => task.summary.push(" ", style),
+                _ => {}
+            }
+        }
+    }
+
+    fn handle_item_end(&mut self, event: &Event) {
+        let Event::End(TagEnd::Item) = event else {
+            return;
+        };
+        let Some(item) = self.hier.pop() else {
+            return;
+        };
+        let Some(mut task) = item.task else {
+            // plain item without nested task is description of parent task
+            if !item.has_nested_task
+                && let Some(task) = self.hier.last_mut().and_then(|parent| parent.task.as_mut())
+            {
+                task.description.push(item.range);
+            }
+            return;
+        };
+        task.summary.trim();
+        let marked_priority = take_priority_marker(&mut task.summary);
+        let inherited = self.inherited_from_tasks();
+        // a task is todo if it or any of its ancestors has a link
+        let agmd = match (task.agmd, &inherited) {
+            (Some(agmd), _) => agmd,
+            (None, Some(_)) => String::new(),
+            (None, None) => return,
+        };
+        let inherited = inherited.unwrap_or_else(|| self.inherited().clone());
+        let mut tags = vec![];
+        let mut assignees = vec![];
+        let mut uid = None;
+        let mut depends = vec![];
+        let reference = &self.options.reference;
+        let attributes = match link_with(&agmd, self.options.date_order) {
+            Ok((_, mut link)) => {
+                // the own marker takes precedence over the inherited priority
+                link.priority = link.priority.or(marked_priority);
+                let link = link.with_defaults(&inherited);
+                tags.clone_from(&link.tags);
+                assignees.clone_from(&link.assignees);
+                uid.clone_from(&link.uid);
+                depends.clone_from(&link.depends);
+                let start = fragment_to_datetime(&link.start, &link.base, Role::Start, reference);
+                let due = due_to_datetime(&link.due, &link.start, &link.base, reference);
+                let completed = match task.status {
+                    Status::Completed => match &link.completed {
+                        Some(_) => {
+                            fragment_to_datetime(&link.completed, &link.base, Role::End, reference)
+                        }
+                        None => due,
+                    },
+                    _ => None,
+                };
+                Ok(Attributes {
+                    status: task.status,
+                    start,
+                    due,
+                    completed,
+                    priority: link.priority,
+                    alarms: link
+                        .alarms
+                        .iter()
+                        .filter_map(|spec| alarm(spec, &link.base, start, due, reference))
+                        .collect(),
+                    estimate: link.estimate,
+                    spent: link.spent,
+                    extensions: link.extensions,
+                })
+            }
+            Err(_) => Err(agmd),
+        };
+        for tag in hashtags(&task.summary) {
+            push_unique(&mut tags, tag);
+        }
+        for assignee in mentions(&task.summary) {
+            push_unique(&mut assignees, assignee);
+        }
+        let content_end = task
+            .description
+            .iter()
+            .map(|range| range.start + self.text[range.clone()].trim_end().len())
+            .fold(task.summary_end, usize::max);
+        // clocks are kept apart from the description
+        let described: Vec<_> = task
+            .description
+            .iter()
+            .filter(|range| !is_clock_block(self.text, range))
+            .cloned()
+            .collect();
+        self.slots[task.slot].1 = Some(Todo {
+            metadata: Metadata {
+                path: self.path.clone(),
+                front_matter: self.front_matter.clone(),
+                headings: self
+                    .headings
+                    .iter()
+                    .map(|(heading, _)| heading.text.clone())
+                    .collect(),
+                line: self.text[..task.marker.start].matches('\n').count() + 1,
+                marker: task.marker,
+                link: task.link,
+                summary_end: task.summary_end,
+                content_end,
+            },
+            summary: task.summary,
+            description: description(self.text, &described),
+            clocks: clocks(self.text, &task.description),
+            uid,
+            depends,
+            prerequisites: vec![],
+            tags,
+            assignees,
+            attributes,
+            parent: None,
+            depth: 0,
+            children: vec![],
+        });
+    }
+}
+
+/// Resolve the alarm with the base, start and due of link,
+/// `None` if the absolute alarm is not a valid date.
+fn alarm(
+    spec: &AlarmSpec,
+    base: &Option<DateTimeFragment>,
+    start: Option<DateTime<Local>>,
+    due: Option<DateTime<Local>>,
+    reference: &Reference,
+) -> Option<Alarm> {
+    match spec {
+        AlarmSpec::Relative { related, offset } => {
+            let related = related.unwrap_or(match due {
+                Some(_) => Related::Due,
+                None => Related::Start,
+            });
+            let date_time = match related {
+                Related::Start => start,
+                Related::Due => due,
+            };
+            // an alarm out of the range of date time is dropped
+            let time = match date_time {
+                Some(date_time) => Some(date_time.checked_add_signed(*offset)?),
+                None => None,
+            };
+            Some(Alarm {
+                trigger: Trigger::Relative {
+                    related,
+                    offset: *offset,
+                },
+                time,
+            })
+        }
+        AlarmSpec::Absolute(fragment) => {
+            let time = fragment_to_datetime(&Some(fragment.clone()), base, Role::Start, reference)?;
+            Some(Alarm {
+                trigger: Trigger::Absolute(time),
+                time: Some(time),
+            })
+        }
+    }
+}
+
+/// Tags of `#tag` in summary, outside code.
+///
+/// A tag consists of alphanumerics, `-`, `_` and `/`, not digits only.
+fn hashtags(summary: &Summary) -> Vec<String> {
+    prefixed_words(summary, '#', |c| matches!(c, '-' | '_' | '/'))
+        .filter(|tag| !tag.chars().all(|c| c.is_ascii_digit()))
+        .collect()
+}
+
+/// Names of `@name` in summary, outside code.
+///
+/// A name consists of alphanumerics, `-`, `_` and `.` not at the end.
+fn mentions(summary: &Summary) -> Vec<String> {
+    prefixed_words(summary, '@', |c| matches!(c, '-' | '_' | '.'))
+        .map(|name| name.trim_end_matches('.').to_string())
+        .filter(|name| !name.is_empty())
+        .collect()
+}
+
+/// Words after `prefix` at the start of a word, outside code.
+fn prefixed_words(
+    summary: &Summary,
+    prefix: char,
+    is_symbol: fn(char) -> bool,
+) -> impl Iterator<Item = String> {
+    let mut words = vec![];
+    for span in summary.spans.iter().filter(|span| !span.style.code) {
+        let text = span.text.as_str();
+        for (i, _) in text.match_indices(prefix) {
+            if !text[..i].chars().last().is_none_or(char::is_whitespace) {
+                continue;
+            }
+            let rest = &text[i + prefix.len_utf8()..];
+            let len = rest
+                .find(|c: char| !(c.is_alphanumeric() || is_symbol(c)))
+                .unwrap_or(rest.len());
+            if len > 0 {
+                words.push(rest[..len].to_string());
+            }
+        }
+    }
+    words.into_iter()
+}
+
+/// Remove the priority marker `!!!`, `!!` or `!` from either end of summary,
+/// returning its priority 1, 5 or 9.
+fn take_priority_marker(summary: &mut Summary) -> Option<u8> {
+    let priority = |word: &str| match word {
+        "!!!" => Some(1),
+        "!!" => Some(5),
+        "!" => Some(9),
+        _ => None,
+    };
+    if let Some(first) = summary.spans.first_mut()
+        && !first.style.code
+    {
+        let word = first.text.split_whitespace().next().unwrap_or_default();
+        if let Some(priority) = priority(word)
+            && first.text[word.len()..].starts_with(char::is_whitespace)
+        {
+            first.text.replace_range(..word.len(), "");
+            summary.trim();
+            return Some(priority);
+        }
+    }
+    if let Some(last) = summary.spans.last_mut()
+        && !last.style.code
+    {
+        let word = last.text.split_whitespace().last().unwrap_or_default();
+        let rest = &last.text[..last.text.len() - word.len()];
+        if let Some(priority) = priority(word)
+            && rest.ends_with(char::is_whitespace)
+        {
+            last.text.truncate(rest.len());
+            summary.trim();
+            return Some(priority);
+        }
+    }
+    None
+}
+
+/// The markdown parser with the extensions of agmd files.
+pub(crate) fn markdown_parser(text: &str) -> Parser<'_> {
+    let mut options = Options::empty();
+    options.insert(Options::ENABLE_TASKLISTS);
+    options.insert(Options::ENABLE_STRIKETHROUGH);
+    options.insert(Options::ENABLE_YAML_STYLE_METADATA_BLOCKS);
+    Parser::new_ext(text, options)
+}
+
+/// Replace custom task markers such as `[-]` with `[ ]`, which are
+/// otherwise not recognized as task. The length of text is kept.
+pub(crate) fn mask_task_markers(text: &str) -> Cow<'_, str> {
+    let mut masked = Cow::Borrowed(text);
+    let mut line_start = 0;
+    for line in text.split_inclusive('\n') {
+        if let Some(offset) = custom_marker_offset(line) {
+            masked
+                .to_mut()
+                .replace_range(line_start + offset..line_start + offset + 1, " ");
+        }
+        line_start += line.len();
+    }
+    masked
+}
+
+/// The offset of custom marker char in the line of task list item, if any.
+fn custom_marker_offset(line: &str) -> Option<usize> {
+    let bytes = line.as_bytes();
+    // indent and block quotes
+    let mut i = bytes
+        .iter()
+        .position(|b| !matches!(b, b' ' | b'\t' | b'>'))?;
+    // bullet or ordered list marker
+    match bytes[i] {
+        b'-' | b'*' | b'+' => i += 1,
+        b'0'..=b'9' => {
+            while bytes.get(i).is_some_and(u8::is_ascii_digit) {
+                i += 1;
+            }
+            if !matches!(bytes.get(i), Some(b'.' | b')')) {
+                return None;
+            }
+            i += 1;
+        }
+        _ => return None,
+    }
+    let spaces = bytes[i..]
+        .iter()
+        .take_while(|b| matches!(b, b' ' | b'\t'))
+        .count();
+    if spaces == 0 {
+        return None;
+    }
+    i += spaces;
+    match bytes.get(i..i + 4)? {
+        [
+            b'[',
+            b'-' | b'/' | b'>' | b'?',
+            b']',
+            b' ' | b'\t' | b'\r' | b'\n',
+        ] => Some(i + 1),
+        _ => None,
+    }
+}
+
+fn is_block(tag: &Tag) -> bool {
+    matches!(
+        tag,
+        Tag::Paragraph
+            | Tag::Heading { .. }
+            | Tag::BlockQuote(_)
+            | Tag::CodeBlock(_)
+            | Tag::HtmlBlock
+            | Tag::List(_)
+            | Tag::FootnoteDefinition(_)
+            | Tag::Table(_)
+    )
+}
+
+fn is_block_end(tag_end: &TagEnd) -> bool {
+    matches!(
+        tag_end,
+        TagEnd::Paragraph
+            | TagEnd::Heading(_)
+            | TagEnd::BlockQuote(_)
+            | TagEnd::CodeBlock
+            | TagEnd::HtmlBlock
+            | TagEnd::List(_)
+            | TagEnd::FootnoteDefinition
+            | TagEnd::Table
+    )
+}
+
+/// Collect the description from source ranges of blocks.
+fn description(text: &str, ranges: &[Range<usize>]) -> Option<Description> {
+    if ranges.is_empty() {
+        return None;
+    }
+    let mut markdown = String::new();
+    let mut last_end = None;
+    for range in ranges {
+        // keep the indent of the first line
+        let line_start = text[..range.start].rfind('\n').map_or(0, |i| i + 1);
+        let start = match text[line_start..range.start].trim().is_empty() {
+            true => line_start,
+            false => range.start,
+        };
+        let end = start + text[start..range.end].trim_end().len();
+        if let Some(last_end) = last_end {
+            // keep blank line between blocks
+            match text[last_end..start].matches('\n').count() > 1 {
+                true => markdown.push_str("\n\n"),
+                false => markdown.push('\n'),
+            }
+        }
+        markdown.push_str(&text[start..end]);
+        last_end = Some(end);
+    }
+    let markdown = dedent(&markdown);
+    let plain = plain_text(&markdown);
+    Some(Description { markdown, plain })
+}
+
+/// Remove the common indent of non-blank lines.
+fn dedent(text: &str) -> String {
+    let indent = text
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| line.len() - line.trim_start().len())
+        .min()
+        .unwrap_or(0);
+    text.lines()
+        .map(|line| line.get(indent..).unwrap_or(line.trim_start()))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// The text of markdown without markup, a line for each block.
+fn plain_text(markdown: &str) -> String {
+    let mut options = Options::empty();
+    options.insert(Options::ENABLE_TASKLISTS);
+    options.insert(Options::ENABLE_STRIKETHROUGH);
+    let mut plain = String::new();
+    for event in Parser::new_ext(markdown, options) {
+        match event {
+            Event::Text(cow_str) | Event::Code(cow_str) => plain.push_str(&cow_str),
+            Event::TaskListMarker(b) => plain.push_str(if b { "[x] " } else { "[ ] " }),
+            Event::SoftBreak | Event::HardBreak => plain.push('\n'),
+            Event::End(tag_end)
+                if (is_block_end(&tag_end) || tag_end == TagEnd::Item)
+                    && !plain.ends_with('\n') =>
+            {
+                plain.push('\n');
+            }
+            _ => {}
+        }
+    }
+    plain.trim_end().to_string()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::convert::of_second;
+
+    fn parse(text: &str) -> Vec<Todo> {
+        let mut acc = vec![];
+        parse_text(&mut acc, "test.md", text, &ParseOptions::default());
+        acc
+    }
+
+    #[test]
+    fn front_matter_base() {
+        let todos = parse(
+            "---\nagmd: 2025-03\nproject: Acme\n---\n\n- [ ] inherit <agmd:>\n- [ ] own <agmd:2025-04>\n",
+        );
+        assert_eq!(todos.len(), 2);
+        assert_eq!(todos[0].metadata.front_matter["project"], "Acme");
+
+        let inherit = todos[0].attributes.as_ref().unwrap();
+        assert_eq!(inherit.start, of_second(2025, 3, 1, 0, 0, 0));
+        assert_eq!(inherit.due, of_second(2025, 4, 1, 0, 0, 0));
+
+        let own = todos[1].attributes.as_ref().unwrap();
+        assert_eq!(own.start, of_second(2025, 4, 1, 0, 0, 0));
+        assert_eq!(own.due, of_second(2025, 5, 1, 0, 0, 0));
+    }
+
+    #[test]
+    fn front_matter_key() {
+        let options = ParseOptions {
+            front_matter_key: "date".to_string(),
+            ..Default::default()
+        };
+        let mut todos = vec![];
+        parse_text(
+            &mut todos,
+            "test.md",
+            "---\ndate: 2025-03-09\n---\n\n- [ ] task <agmd:>\n",
+            &options,
+        );
+        let attributes = todos[0].attributes.as_ref().unwrap();
+        assert_eq!(attributes.due, of_second(2025, 3, 10, 0, 0, 0));
+    }
+
+    #[test]
+    fn path_pattern() {
+        let options = ParseOptions {
+            path_patterns: vec![
+                PathPattern::new("{year}-{month}-{day}.md").unwrap(),
+                PathPattern::new("{year}/W{week}.md").unwrap(),
+            ],
+            ..Default::default()
+        };
+        let mut todos = vec![];
+        parse_text(
+            &mut todos,
+            "journal/2025/2025-03-09.md",
+            "- [ ] bare <agmd:>\n- [ ] due <agmd:due=12>\n- [ ] own <agmd:2025-04-01>\n",
+            &options,
+        );
+        let bare = todos[0].attributes.as_ref().unwrap();
+        assert_eq!(bare.start, of_second(2025, 3, 9, 0, 0, 0));
+        assert_eq!(bare.due, of_second(2025, 3, 10, 0, 0, 0));
+        let due = todos[1].attributes.as_ref().unwrap();
+        assert_eq!(due.start, of_second(2025, 3, 9, 0, 0, 0));
+        assert_eq!(due.due, of_second(2025, 3, 13, 0, 0, 0));
+        let own = todos[2].attributes.as_ref().unwrap();
+        assert_eq!(own.due, of_second(2025, 4, 2, 0, 0, 0));
+
+        // the whole week, Monday to Sunday
+        let mut todos = vec![];
+        parse_text(
+            &mut todos,
+            "plan/2025/W11.md",
+            "- [ ] bare <agmd:>\n- [ ] due <agmd:due=14>\n",
+            &options,
+        );
+        let bare = todos[0].attributes.as_ref().unwrap();
+        assert_eq!(bare.start, of_second(2025, 3, 10, 0, 0, 0));
+        assert_eq!(bare.due, of_second(2025, 3, 17, 0, 0, 0));
+        let due = todos[1].attributes.as_ref().unwrap();
+        assert_eq!(due.due, of_second(2025, 3, 15, 0, 0, 0));
+
+        // front matter takes precedence over path
+        let mut todos = vec![];
+        parse_text(
+            &mut todos,
+            "journal/2025/2025-03-09.md",
+            "---\nagmd: 2025-05\n---\n\n- [ ] task <agmd:>\n",
+            &options,
+        );
+        let attributes = todos[0].attributes.as_ref().unwrap();
+        assert_eq!(attributes.due, of_second(2025, 6, 1, 0, 0, 0));
+    }
+
+    #[test]
+    fn heading_link() {
+        let todos = parse(
+            "# Plan <agmd:2025>\n\
+             ## Sprint <agmd:2025-03-10;due=2025-03-21>\n\
+             - [ ] inherit <agmd:>\n\
+             - [ ] override due <agmd:due=2025-03-14>\n\
+             ### Detail\n\
+             - [ ] nested heading <agmd:>\n\
+             ## Other\n\
+             - [ ] outer heading <agmd:>\n",
+        );
+        let attributes = |i: usize| todos[i].attributes.as_ref().unwrap();
+
+        assert_eq!(attributes(0).start, of_second(2025, 3, 10, 0, 0, 0));
+        assert_eq!(attributes(0).due, of_second(2025, 3, 22, 0, 0, 0));
+
+        assert_eq!(attributes(1).start, of_second(2025, 3, 10, 0, 0, 0));
+        assert_eq!(attributes(1).due, of_second(2025, 3, 15, 0, 0, 0));
+
+        assert_eq!(attributes(2).due, of_second(2025, 3, 22, 0, 0, 0));
+
+        assert_eq!(attributes(3).start, of_second(2025, 1, 1, 0, 0, 0));
+        assert_eq!(attributes(3).due, of_second(2026, 1, 1, 0, 0, 0));
+    }
+
+    #[test]
+    fn extensions() {
+        let todos = parse(
+            "## Ops <agmd:project=infra;ticket=OPS-1>\n\
+             - [ ] own ticket <agmd:ticket=OPS-123;url=\"https://x.test/?a=1;b=2\">\n\
+             - [ ] inherit <agmd:>\n",
+        );
+        let attributes = |i: usize| todos[i].attributes.as_ref().unwrap();
+        assert_eq!(attributes(0).extension("project"), Some("infra"));
+        assert_eq!(attributes(0).extension("ticket"), Some("OPS-123"));
+        assert_eq!(
+            attributes(0).extension("url"),
+            Some("https://x.test/?a=1;b=2")
+        );
+        assert_eq!(attributes(1).extension("ticket"), Some("OPS-1"));
+        assert_eq!(attributes(1).extension("url"), None);
+    }
+
+    #[test]
+    fn heading_path() {
+        let todos = parse(
+            "- [ ] top <agmd:>\n\
+             # Clients\n\
+             ## Acme `v2` <agmd:2025>\n\
+             ### Q2\n\
+             - [ ] deep <agmd:>\n\
+             ## Other\n\
+             - [ ] shallow <agmd:>\n",
+        );
+        assert!(todos[0].metadata.headings.is_empty());
+        assert_eq!(todos[1].metadata.headings, ["Clients", "Acme v2", "Q2"]);
+        assert_eq!(todos[1].metadata.context(), "Clients > Acme v2 > Q2");
+        assert_eq!(todos[2].metadata.headings, ["Clients", "Other"]);
+    }
+
+    #[test]
+    fn subtasks() {
+        let todos = parse(
+            "- [ ] parent <agmd:2025-03-09>\n\
+             \x20 - [x] child\n\
+             \x20 - plain\n\
+             \x20   - [ ] grandchild <agmd:due=2025-03-12>\n\
+             \x20 - [ ] malformed <agmd:x>\n\
+             - [ ] without link\n\
+             \x20 - [ ] orphan <agmd:2025-04>\n",
+        );
+        let summaries: Vec<_> = todos.iter().map(|t| t.summary.plain()).collect();
+        assert_eq!(
+            summaries,
+            ["parent", "child", "grandchild", "malformed", "orphan"]
+        );
+
+        assert_eq!(todos[0].parent, None);
+        assert_eq!(todos[0].children, [1, 2, 3]);
+        assert_eq!(todos[1].parent, Some(0));
+        assert_eq!(todos[1].depth, 1);
+        assert_eq!(todos[2].parent, Some(0));
+        assert_eq!(todos[4].parent, None);
+        assert_eq!(todos[4].depth, 0);
+
+        // inherit the base of parent
+        let child = todos[1].attributes.as_ref().unwrap();
+        assert_eq!(child.due, of_second(2025, 3, 10, 0, 0, 0));
+        let grandchild = todos[2].attributes.as_ref().unwrap();
+        assert_eq!(grandchild.start, of_second(2025, 3, 9, 0, 0, 0));
+        assert_eq!(grandchild.due, of_second(2025, 3, 13, 0, 0, 0));
+
+        let progress = todos[0].progress(&todos).unwrap();
+        assert_eq!((progress.done, progress.total), (1, 3));
+        assert_eq!(todos[1].progress(&todos), None);
+    }
+
+    #[test]
+    fn description() {
+        let todos = parse(
+            "- [ ] release <agmd:2025-03-09>\n\
+             \n\
+             \x20 Bump the *version* first.\n\
+             \n\
+             \x20 ```sh\n\
+             \x20 cargo publish\n\
+             \x20 ```\n\
+             \n\
+             \x20 - tag the commit\n\
+             \x20 - [ ] announce\n\
+             - [ ] plain <agmd:2025-03-10>\n",
+        );
+        let description = todos[0].description.as_ref().unwrap();
+        assert_eq!(
+            description.markdown,
+            "Bump the *version* first.\n\n```sh\ncargo publish\n```\n\n- tag the commit"
+        );
+        assert_eq!(
+            description.plain,
+            "Bump the version first.\ncargo publish\ntag the commit"
+        );
+        // subtask is not part of description
+        assert_eq!(todos[1].summary.plain(), "announce");
+        assert_eq!(todos[1].description, None);
+        assert_eq!(todos[2].description, None);
+    }
+
+    #[test]
+    fn status() {
+        let todos = parse(
+            "- [ ] todo <agmd:2025-03-09>\n\
+             - [/] doing <agmd:2025-03-09>\n\
+             - [x] done <agmd:2025-03-09>\n\
+             - [-] cancelled <agmd:2025-03-09>\n\
+             - [>] deferred <agmd:2025-03-09>\n\
+             \x20 1. [?] question\n\
+             > - [-] quoted <agmd:2025-03-09>\n\
+             - [~] unknown <agmd:2025-03-09>\n",
+        );
+        let statuses: Vec<_> = todos
+            .iter()
+            .map(|t| (t.summary.plain(), t.attributes.as_ref().unwrap().status))
+            .collect();
+        assert_eq!(
+            statuses,
+            [
+                ("todo".to_string(), Status::NeedsAction),
+                ("doing".to_string(), Status::InProcess),
+                ("done".to_string(), Status::Completed),
+                ("cancelled".to_string(), Status::Cancelled),
+                ("deferred".to_string(), Status::Deferred),
+                ("question".to_string(), Status::Question),
+                ("quoted".to_string(), Status::Cancelled),
+            ]
+        );
+        // only completed todo has completion date
+        assert!(todos[2].attributes.as_ref().unwrap().is_completed());
+        assert!(!todos[3].attributes.as_ref().unwrap().is_completed());
+
+        let progress = todos[4].progress(&todos).unwrap();
+        assert_eq!((progress.done, progress.total), (0, 1));
+    }
+
+    #[test]
+    fn priority() {
+        let todos = parse(
+            "## Urgent <agmd:2025-03-09;priority=2>\n\
+             - [ ] inherited <agmd:>\n\
+             - [ ] !!! marked first <agmd:2025-03-09>\n\
+             - [ ] marked **last** ! <agmd:2025-03-09>\n\
+             - [ ] key wins !! <agmd:2025-03-09;priority=low>\n\
+             - [ ] not `!!` <agmd:2025-03-09>\n",
+        );
+        let priorities: Vec<_> = todos
+            .iter()
+            .map(|t| (t.summary.plain(), t.attributes.as_ref().unwrap().priority))
+            .collect();
+        assert_eq!(
+            priorities,
+            [
+                ("inherited".to_string(), Some(2)),
+                ("marked first".to_string(), Some(1)),
+                ("marked last".to_string(), Some(9)),
+                ("key wins".to_string(), Some(9)),
+                // inherited, as `!!` in code is not a marker
+                ("not !!".to_string(), Some(2)),
+            ]
+        );
+    }
+
+    #[test]
+    fn tags() {
+        let todos = parse(
+            "## Hiring <agmd:2025-03-09;tags=hiring>\n\
+             - [ ] screen #Ops and #review, not `#code` <agmd:tags=ops>\n\
+             - [ ] issue#1 and #42 are not tags <agmd:>\n",
+        );
+        assert_eq!(todos[0].tags, ["hiring", "ops", "review"]);
+        assert!(todos[0].has_tag("OPS"));
+        assert_eq!(todos[1].tags, ["hiring"]);
+    }
+
+    #[test]
+    fn assignees() {
+        let todos = parse(
+            "## Alice <agmd:2025-03-09;assignee=alice>\n\
+             - [ ] inherited <agmd:>\n\
+             - [ ] ask @Bob and @carol. not a@b.c or `@code` <agmd:>\n\
+             - [ ] review with @bob <agmd:assignee=bob>\n",
+        );
+        assert_eq!(todos[0].assignees, ["alice"]);
+        assert_eq!(todos[1].assignees, ["alice", "Bob", "carol"]);
+        assert_eq!(todos[2].assignees, ["bob"]);
+        assert!(todos[1].is_assigned_to("bob"));
+    }
+
+    #[test]
+    fn assignee_of_dated_task() {
+        let todos = parse(
+            "---\nagmd: assignee=carol\n---\n\
+             - [ ] from front matter <agmd:2025-03-12>\n\
+             ## Alice <agmd:2025-03;assignee=alice>\n\
+             - [ ] own date <agmd:2025-03-09;due=12>\n\
+             \x20 - [ ] under dated parent <agmd:2025-03-10>\n",
+        );
+        assert_eq!(todos[0].assignees, ["carol"]);
+        assert_eq!(todos[1].assignees, ["alice"]);
+        assert_eq!(todos[2].assignees, ["alice"]);
+        let attributes = todos[1].attributes.as_ref().unwrap();
+        assert_eq!(attributes.start, of_second(2025, 3, 9, 0, 0, 0));
+        assert_eq!(attributes.due, of_second(2025, 3, 13, 0, 0, 0));
+    }
+
+    #[test]
+    fn alarms() {
+        let todos = parse(
+            "- [ ] a <agmd:2025-03-09;start=2025-03-08;alarm=-PT15M,start-P1D,T09:30>\n\
+             - [ ] b <agmd:start=2025-03-09T10;alarm=-PT1H>\n",
+        );
+        let times: Vec<_> = todos
+            .iter()
+            .map(|t| {
+                let agmd = t.attributes.as_ref().unwrap();
+                agmd.alarms
+                    .iter()
+                    .map(|alarm| alarm.time)
+                    .collect::<Vec<_>>()
+            })
+            .collect();
+        assert_eq!(
+            times,
+            [
+                vec![
+                    of_second(2025, 3, 9, 23, 45, 0),
+                    of_second(2025, 3, 7, 0, 0, 0),
+                    of_second(2025, 3, 9, 9, 30, 0),
+                ],
+                // relative to start without due
+                vec![of_second(2025, 3, 9, 9, 0, 0)],
+            ]
+        );
+    }
+
+    #[test]
+    fn alarm_of_dated_task() {
+        let todos = parse(
+            "## Release <agmd:2025-03;alarm=-PT15M>\n\
+             - [ ] own date <agmd:2025-03-09>\n\
+             - [ ] own alarm <agmd:2025-03-10;alarm=-PT1H>\n",
+        );
+        let times: Vec<_> = todos
+            .iter()
+            .map(|t| t.attributes.as_ref().unwrap().alarms[0].time)
+            .collect();
+        assert_eq!(
+            times,
+            [
+                of_second(2025, 3, 9, 23, 45, 0),
+                of_second(2025, 3, 10, 23, 0, 0)
+            ]
+        );
+    }
+
+    #[test]
+    fn alarm_out_of_range() {
+        let todos = parse(
+            "- [ ] a <agmd:2025-03-09;alarm=-P99999999999999W>\n\
+             - [ ] b <agmd:2025-03-09;alarm=-P999999999D,-PT15M>\n",
+        );
+        // an offset out of range is malformed
+        assert!(todos[0].attributes.is_err());
+        // a time out of range is dropped
+        let agmd = todos[1].attributes.as_ref().unwrap();
+        let times: Vec<_> = agmd.alarms.iter().map(|alarm| alarm.time).collect();
+        assert_eq!(times, [of_second(2025, 3, 9, 23, 45, 0)]);
+    }
+
+    #[test]
+    fn mask_markers() {
+        let text = "- [-] a\n  * [/] b\n1) [?] c\n-[-] d\n- [-]x\n`[-]`\n";
+        assert_eq!(
+            mask_task_markers(text),
+            "- [ ] a\n  * [ ] b\n1) [ ] c\n-[-] d\n- [-]x\n`[-]`\n"
+        );
+    }
+
+    #[test]
+    fn rich_summary() {
+        let todos = parse(
+            "- [ ] Fix `parse_file` *bug*\n  in **[docs](https://example.com)** ~~now~~ <agmd:>\n",
+        );
+        let summary = &todos[0].summary;
+        assert_eq!(summary.plain(), "Fix parse_file bug in docs now");
+
+        let style = |code, emphasis, strong, strikethrough, link| SpanStyle {
+            code,
+            emphasis,
+            strong,
+            strikethrough,
+            link,
+        };
+        let spans: Vec<_> = summary
+            .spans
+            .iter()
+            .map(|span| (span.text.as_str(), span.style))
+            .collect();
+        assert_eq!(
+            spans,
+            [
+                ("Fix ", SpanStyle::default()),
+                ("parse_file", style(true, false, false, false, false)),
+                (" ", SpanStyle::default()),
+                ("bug", style(false, true, false, false, false)),
+                (" in ", SpanStyle::default()),
+                ("docs", style(false, false, true, false, true)),
+                (" ", SpanStyle::default()),
+                ("now", style(false, false, false, true, false)),
+            ]
+        );
+    }
+
+    #[test]
+    fn without_front_matter() {
+        let todos = parse("- [ ] task <agmd:>\n");
+        assert!(todos[0].metadata.front_matter.is_empty());
+        assert_eq!(todos[0].attributes.as_ref().unwrap().due, None);
     }
 }
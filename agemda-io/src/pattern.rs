@@ -0,0 +1,143 @@
+use std::path::Path;
+
+use anyhow::bail;
+use chrono::{NaiveDate, Weekday};
+use regex::Regex;
+
+use crate::fragment::DateTimeFragment;
+
+/// A pattern of file path to infer the base fragment of the file,
+/// such as `{year}-{month}-{day}.md` or `{year}/W{week}.md`.
+///
+/// The pattern is matched against the trailing components of the path.
+#[derive(Debug, Clone)]
+pub struct PathPattern {
+    source: String,
+    regex: Regex,
+}
+
+impl PartialEq for PathPattern {
+    fn eq(&self, other: &Self) -> bool {
+        self.source == other.source
+    }
+}
+
+impl PathPattern {
+    pub fn new(source: &str) -> anyhow::Result<Self> {
+        let mut regex = String::from("(?:^|/)");
+        let mut rest = source;
+        while let Some(open) = rest.find('{') {
+            regex.push_str(&regex::escape(&rest[..open]));
+            let Some(close) = rest[open..].find('}') else {
+                bail!("unclosed placeholder in path pattern {source}");
+            };
+            let name = &rest[open + 1..open + close];
+            let digits = match name {
+                "year" => 4,
+                "month" | "day" | "week" => 2,
+                _ => bail!("unknown placeholder {{{name}}} in path pattern {source}"),
+            };
+            regex.push_str(&format!("(?P<{name}>[0-9]{{{digits}}})"));
+            rest = &rest[open + close + 1..];
+        }
+        regex.push_str(&regex::escape(rest));
+        regex.push('$');
+
+        let regex = Regex::new(&regex)?;
+        if regex.capture_names().flatten().all(|name| name != "year") {
+            bail!("path pattern {source} has no {{year}}");
+        }
+        Ok(Self {
+            source: source.to_string(),
+            regex,
+        })
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.source
+    }
+
+    /// The base fragment inferred from the path, if matched.
+    pub fn base_of(&self, path: &Path) -> Option<DateTimeFragment> {
+        let path = path
+            .components()
+            .map(|c| c.as_os_str().to_string_lossy())
+            .collect::<Vec<_>>()
+            .join("/");
+        let captures = self.regex.captures(&path)?;
+        let number = |name| captures.name(name).and_then(|m| m.as_str().parse().ok());
+
+        let year = number("year")? as i32;
+        if let Some(week) = number("week") {
+            NaiveDate::from_isoywd_opt(year, week, Weekday::Mon)?;
+            return Some(DateTimeFragment::from_yw(year, week));
+        }
+        let fragment = match (number("month"), number("day")) {
+            (Some(month), Some(day)) => {
+                NaiveDate::from_ymd_opt(year, month, day)?;
+                DateTimeFragment::from_ymd(year, month, day)
+            }
+            (Some(month), None) => {
+                NaiveDate::from_ymd_opt(year, month, 1)?;
+                DateTimeFragment::from_ym(year, month)
+            }
+            (None, _) => DateTimeFragment::from_y(year),
+        };
+        Some(fragment)
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::zero_prefixed_literal)]
+mod test {
+    use super::*;
+
+    fn base_of(pattern: &str, path: &str) -> Option<DateTimeFragment> {
+        PathPattern::new(pattern).unwrap().base_of(Path::new(path))
+    }
+
+    #[test]
+    fn day() {
+        assert_eq!(
+            base_of("{year}-{month}-{day}.md", "journal/2025/2025-03-09.md"),
+            Some(DateTimeFragment::from_ymd(2025, 03, 09))
+        );
+    }
+
+    #[test]
+    fn directories() {
+        assert_eq!(
+            base_of("{year}/{month}.md", "notes/2025/03.md"),
+            Some(DateTimeFragment::from_ym(2025, 03))
+        );
+    }
+
+    #[test]
+    fn week() {
+        assert_eq!(
+            base_of("{year}/W{week}.md", "plan/2025/W11.md"),
+            Some(DateTimeFragment::from_yw(2025, 11))
+        );
+    }
+
+    #[test]
+    fn component_boundary() {
+        assert_eq!(base_of("{year}-{month}-{day}.md", "x2025-03-09.md"), None);
+        assert_eq!(
+            base_of("{year}-{month}-{day}.md", "2025-03-09.md.bak"),
+            None
+        );
+    }
+
+    #[test]
+    fn invalid_date() {
+        assert_eq!(base_of("{year}-{month}-{day}.md", "2025-02-30.md"), None);
+    }
+
+    #[test]
+    fn invalid_pattern() {
+        assert!(PathPattern::new("{month}-{day}.md").is_err());
+        assert!(PathPattern::new("{year}-{hour}.md").is_err());
+        assert!(PathPattern::new("{year.md").is_err());
+    }
+}
@@ -0,0 +1,42 @@
+use std::{
+    collections::HashSet,
+    hash::{BuildHasher, RandomState},
+};
+
+/// Length of generated uid, 36^6 ≈ 2e9 possibilities.
+const LENGTH: usize = 6;
+
+/// Generate a short random uid of lowercase alphanumerics, not in `used`.
+pub fn generate(used: &HashSet<String>) -> String {
+    let state = RandomState::new();
+    (0u64..)
+        .map(|seed| base36(state.hash_one(seed)))
+        .find(|uid| !used.contains(uid))
+        .expect("fail to generate uid")
+}
+
+fn base36(mut n: u64) -> String {
+    const DIGITS: &[u8] = b"0123456789abcdefghijklmnopqrstuvwxyz";
+    let mut uid = String::with_capacity(LENGTH);
+    for _ in 0..LENGTH {
+        uid.push(DIGITS[(n % 36) as usize] as char);
+        n /= 36;
+    }
+    uid
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn unique() {
+        let mut used = HashSet::new();
+        for _ in 0..1000 {
+            let uid = generate(&used);
+            assert_eq!(uid.len(), LENGTH);
+            assert!(uid.chars().all(|c| c.is_ascii_alphanumeric()));
+            assert!(used.insert(uid));
+        }
+    }
+}
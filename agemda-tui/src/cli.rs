@@ -1,5 +1,6 @@
-use std::{env::current_dir, path::PathBuf};
+use std::{env::current_dir, path::PathBuf, str::FromStr};
 
+use agemda_core::Status;
 use argh::FromArgs;
 
 #[derive(Clone, Debug, FromArgs)]
@@ -8,6 +9,180 @@ pub struct Cli {
     /// the root path to search for md files
     #[argh(positional, default = "default_root()")]
     pub root: PathBuf,
+
+    /// the config file, defaults to `agemda/config.toml` under the config dir
+    #[argh(option)]
+    pub config: Option<PathBuf>,
+
+    /// run a command instead of the calendar
+    #[argh(subcommand)]
+    pub command: Option<Command>,
+}
+
+#[derive(Clone, Debug, FromArgs)]
+#[argh(subcommand)]
+pub enum Command {
+    List(ListCommand),
+    Export(ExportCommand),
+    Ids(IdsCommand),
+    Check(CheckCommand),
+    Done(DoneCommand),
+    Notify(NotifyCommand),
+    Report(ReportCommand),
+    Fmt(FmtCommand),
+}
+
+#[derive(Clone, Debug, FromArgs)]
+/// Print todos, one per line.
+#[argh(subcommand, name = "list")]
+pub struct ListCommand {
+    /// only todos whose heading path contains this text
+    #[argh(option)]
+    pub context: Option<String>,
+
+    /// only todos with this tag, without `#`
+    #[argh(option)]
+    pub tag: Option<String>,
+
+    /// only todos assigned to this name, defaults to `me` of config
+    #[argh(option)]
+    pub assignee: Option<String>,
+
+    /// include todos of everyone instead of `me` of config
+    #[argh(switch)]
+    pub everyone: bool,
+
+    /// only todos whose link has this key, or `key=value`, e.g. `ticket=OPS-123`
+    #[argh(option)]
+    pub ext: Option<String>,
+
+    /// only todos of this status, e.g. `in-process` or `cancelled`
+    #[argh(option)]
+    pub status: Option<Status>,
+
+    /// include completed and cancelled todos
+    #[argh(switch, short = 'a')]
+    pub all: bool,
+
+    /// group todos by heading path
+    #[argh(switch)]
+    pub group: bool,
+}
+
+#[derive(Clone, Debug, FromArgs)]
+/// Print todos as iCalendar.
+#[argh(subcommand, name = "export")]
+pub struct ExportCommand {
+    /// only todos whose heading path contains this text
+    #[argh(option)]
+    pub context: Option<String>,
+
+    /// only todos with this tag, without `#`
+    #[argh(option)]
+    pub tag: Option<String>,
+
+    /// only todos assigned to this name, defaults to `me` of config
+    #[argh(option)]
+    pub assignee: Option<String>,
+
+    /// include todos of everyone instead of `me` of config
+    #[argh(switch)]
+    pub everyone: bool,
+
+    /// only todos whose link has this key, or `key=value`, e.g. `ticket=OPS-123`
+    #[argh(option)]
+    pub ext: Option<String>,
+
+    /// only todos of this status, e.g. `in-process` or `cancelled`
+    #[argh(option)]
+    pub status: Option<Status>,
+
+    /// include completed and cancelled todos
+    #[argh(switch, short = 'a')]
+    pub all: bool,
+}
+
+#[derive(Clone, Debug, FromArgs)]
+/// Assign uids to todos without one, by rewriting their links.
+#[argh(subcommand, name = "ids")]
+pub struct IdsCommand {
+    /// print the todos to be assigned without writing files
+    #[argh(switch)]
+    pub dry_run: bool,
+}
+
+#[derive(Clone, Debug, FromArgs)]
+/// Report problems of todos, such as malformed links and duplicate uids.
+#[argh(subcommand, name = "check")]
+pub struct CheckCommand {}
+
+#[derive(Clone, Debug, FromArgs)]
+/// Mark the todo of uid as completed today.
+#[argh(subcommand, name = "done")]
+pub struct DoneCommand {
+    /// the uid of todo
+    #[argh(positional)]
+    pub uid: String,
+}
+
+#[derive(Clone, Debug, FromArgs)]
+/// Watch the root and run `notify-command` of config when alarms come due.
+#[argh(subcommand, name = "notify")]
+pub struct NotifyCommand {
+    /// seconds between checks, defaults to 30
+    #[argh(option, default = "30")]
+    pub interval: u64,
+
+    /// check once and exit
+    #[argh(switch)]
+    pub once: bool,
+}
+
+#[derive(Clone, Debug, FromArgs)]
+/// Sum estimated and spent hours of todos by day, tag or file.
+#[argh(subcommand, name = "report")]
+pub struct ReportCommand {
+    /// group by `day`, `tag` or `file`, defaults to day
+    #[argh(option, default = "ReportBy::Day")]
+    pub by: ReportBy,
+
+    /// only todos assigned to this name, defaults to `me` of config
+    #[argh(option)]
+    pub assignee: Option<String>,
+
+    /// include todos of everyone instead of `me` of config
+    #[argh(switch)]
+    pub everyone: bool,
+}
+
+#[derive(Clone, Debug, FromArgs)]
+/// Rewrite agmd links in canonical form.
+#[argh(subcommand, name = "fmt")]
+pub struct FmtCommand {
+    /// print the diff of files to be rewritten without writing them, failing if any
+    #[argh(switch)]
+    pub check: bool,
+}
+
+/// The grouping of report.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ReportBy {
+    Day,
+    Tag,
+    File,
+}
+
+impl FromStr for ReportBy {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "day" => Ok(ReportBy::Day),
+            "tag" => Ok(ReportBy::Tag),
+            "file" => Ok(ReportBy::File),
+            _ => Err(format!("unknown grouping {s}, expect day, tag or file")),
+        }
+    }
 }
 
 fn default_root() -> PathBuf {
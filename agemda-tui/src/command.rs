@@ -0,0 +1,52 @@
+pub mod check;
+pub mod done;
+pub mod export;
+pub mod fmt;
+pub mod ids;
+pub mod list;
+pub mod notify;
+pub mod report;
+
+use std::path::Path;
+
+use agemda_core::Todo;
+use agemda_io::check::duplicate_uids;
+
+use crate::{cli::Command, config::Config};
+
+/// Run the command on todos loaded from root.
+pub fn run(command: &Command, root: &Path, todos: &[Todo], config: &Config) -> anyhow::Result<()> {
+    // check reports all problems itself
+    if !matches!(command, Command::Check(_)) {
+        for problem in duplicate_uids(todos) {
+            eprintln!("warning: {}", problem.message(todos));
+        }
+    }
+    match command {
+        Command::List(command) => list::run(command, todos, config),
+        Command::Export(command) => export::run(command, todos, config),
+        Command::Ids(command) => ids::run(command, todos),
+        Command::Check(command) => check::run(command, todos),
+        Command::Done(command) => done::run(command, todos),
+        Command::Notify(command) => notify::run(command, root, config),
+        Command::Report(command) => report::run(command, todos, config),
+        Command::Fmt(command) => fmt::run(command, root, config),
+    }
+}
+
+/// The name of own todos to filter by, `me` unless an assignee is given or for everyone.
+fn me(given: &Option<String>, everyone: bool, config: &Config) -> Option<String> {
+    match (given, everyone) {
+        (None, false) => config.me.clone(),
+        _ => None,
+    }
+}
+
+/// The extension to filter by, of `key` or `key=value`.
+fn extension(given: &Option<String>) -> Option<(String, Option<String>)> {
+    let given = given.as_ref()?;
+    Some(match given.split_once('=') {
+        Some((key, value)) => (key.to_string(), Some(value.to_string())),
+        None => (given.clone(), None),
+    })
+}
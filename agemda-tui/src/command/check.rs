@@ -0,0 +1,16 @@
+use agemda_core::Todo;
+use agemda_io::check::check;
+use anyhow::bail;
+
+use crate::cli::CheckCommand;
+
+pub fn run(_command: &CheckCommand, todos: &[Todo]) -> anyhow::Result<()> {
+    let problems = check(todos);
+    for problem in &problems {
+        println!("{}", problem.message(todos));
+    }
+    if !problems.is_empty() {
+        bail!("{} problem(s) found", problems.len());
+    }
+    Ok(())
+}
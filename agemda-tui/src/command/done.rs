@@ -0,0 +1,41 @@
+use std::fs;
+
+use agemda_core::{Status, Todo};
+use agemda_io::{
+    check::location,
+    edit::{add_key, apply_to_files, set_status},
+};
+use anyhow::{bail, Context};
+use chrono::Local;
+
+use crate::cli::DoneCommand;
+
+pub fn run(command: &DoneCommand, todos: &[Todo]) -> anyhow::Result<()> {
+    let matched: Vec<_> = todos
+        .iter()
+        .filter(|todo| todo.uid.as_deref() == Some(command.uid.as_str()))
+        .collect();
+    let todo = match matched.as_slice() {
+        [todo] => todo,
+        [] => bail!("no todo of uid {}", command.uid),
+        _ => bail!("duplicate uid {}", command.uid),
+    };
+    if todo
+        .attributes
+        .as_ref()
+        .is_ok_and(|agmd| agmd.status == Status::Completed)
+    {
+        println!("{} is already completed", location(todo));
+        return Ok(());
+    }
+    let path = &todo.metadata.path;
+    let text = fs::read_to_string(path)
+        .with_context(|| format!("fail to read file {}", path.display()))?;
+    let today = Local::now().format("%Y-%m-%d").to_string();
+    apply_to_files(&[
+        set_status(&text, todo, Status::Completed),
+        add_key(&text, todo, "completed", &today)?,
+    ])?;
+    println!("{} [x] {}", location(todo), todo.summary);
+    Ok(())
+}
@@ -0,0 +1,21 @@
+use std::io;
+
+use agemda_core::{Filter, Todo};
+use agemda_io::ical::write_calendar;
+
+use super::{extension, me};
+use crate::{cli::ExportCommand, config::Config};
+
+pub fn run(command: &ExportCommand, todos: &[Todo], config: &Config) -> anyhow::Result<()> {
+    let filter = Filter {
+        context: command.context.clone(),
+        status: command.status,
+        tag: command.tag.clone(),
+        assignee: command.assignee.clone(),
+        me: me(&command.assignee, command.everyone, config),
+        should_show_completed: command.all,
+        extension: extension(&command.ext),
+    };
+    write_calendar(&mut io::stdout().lock(), todos, |todo| filter.matches(todo))?;
+    Ok(())
+}
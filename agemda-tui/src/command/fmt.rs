@@ -0,0 +1,49 @@
+use std::{fs, path::Path};
+
+use agemda_io::{
+    edit::{apply, canonical_links},
+    load::walk_markdown_files,
+};
+use anyhow::{bail, Context};
+
+use crate::{cli::FmtCommand, config::Config};
+
+pub fn run(command: &FmtCommand, root: &Path, config: &Config) -> anyhow::Result<()> {
+    let options = config.parse_options()?;
+    let mut changed = 0;
+    for path in walk_markdown_files(root) {
+        let path = path?;
+        let text = fs::read_to_string(&path)
+            .with_context(|| format!("fail to read file {}", path.display()))?;
+        let edits = canonical_links(&path, &text, &options);
+        if edits.is_empty() {
+            continue;
+        }
+        let formatted = apply(&text, &edits.iter().collect::<Vec<_>>())?;
+        if command.check {
+            print_diff(&path, &text, &formatted);
+        } else {
+            fs::write(&path, formatted)
+                .with_context(|| format!("fail to write file {}", path.display()))?;
+            println!("{}", path.display());
+        }
+        changed += 1;
+    }
+    if command.check && changed > 0 {
+        bail!("{changed} file(s) not formatted");
+    }
+    Ok(())
+}
+
+/// Print the changed lines, as links are rewritten within their lines.
+fn print_diff(path: &Path, old: &str, new: &str) {
+    println!("--- {}", path.display());
+    println!("+++ {}", path.display());
+    for (index, (old, new)) in old.lines().zip(new.lines()).enumerate() {
+        if old != new {
+            println!("@@ line {} @@", index + 1);
+            println!("-{old}");
+            println!("+{new}");
+        }
+    }
+}
@@ -0,0 +1,41 @@
+use std::{
+    collections::{HashMap, HashSet},
+    fs,
+};
+
+use agemda_core::Todo;
+use agemda_io::{
+    check::location,
+    edit::{add_key, apply_to_files},
+    uid::generate,
+};
+use anyhow::Context;
+
+use crate::cli::IdsCommand;
+
+pub fn run(command: &IdsCommand, todos: &[Todo]) -> anyhow::Result<()> {
+    let mut used: HashSet<_> = todos.iter().filter_map(|todo| todo.uid.clone()).collect();
+    let mut edits = vec![];
+    // content of files, read once each
+    let mut texts = HashMap::new();
+    for todo in todos.iter().filter(|todo| todo.uid.is_none()) {
+        // malformed link can not be extended
+        if todo.attributes.is_err() {
+            continue;
+        }
+        let path = &todo.metadata.path;
+        if !texts.contains_key(path) {
+            let text = fs::read_to_string(path)
+                .with_context(|| format!("fail to read file {}", path.display()))?;
+            texts.insert(path, text);
+        }
+        let uid = generate(&used);
+        println!("{} {uid} {}", location(todo), todo.summary);
+        edits.push(add_key(&texts[path], todo, "uid", &uid)?);
+        used.insert(uid);
+    }
+    if !command.dry_run {
+        apply_to_files(&edits)?;
+    }
+    Ok(())
+}
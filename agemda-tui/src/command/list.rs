@@ -0,0 +1,68 @@
+use std::io::{self, Write};
+
+use agemda_core::{Filter, Todo};
+
+use super::{extension, me};
+use crate::{cli::ListCommand, config::Config};
+
+pub fn run(command: &ListCommand, todos: &[Todo], config: &Config) -> anyhow::Result<()> {
+    let filter = Filter {
+        context: command.context.clone(),
+        status: command.status,
+        tag: command.tag.clone(),
+        assignee: command.assignee.clone(),
+        me: me(&command.assignee, command.everyone, config),
+        should_show_completed: command.all,
+        extension: extension(&command.ext),
+    };
+    let filtered: Vec<_> = todos.iter().filter(|todo| filter.matches(todo)).collect();
+
+    let mut stdout = io::stdout().lock();
+    if command.group {
+        // groups in order of first appearance
+        let mut groups: Vec<(String, Vec<&Todo>)> = vec![];
+        for todo in filtered {
+            let context = todo.metadata.context();
+            match groups.iter_mut().find(|(c, _)| *c == context) {
+                Some((_, group)) => group.push(todo),
+                None => groups.push((context, vec![todo])),
+            }
+        }
+        for (context, group) in groups {
+            match context.is_empty() {
+                true => writeln!(stdout, "(no heading)")?,
+                false => writeln!(stdout, "{context}")?,
+            }
+            for todo in group {
+                writeln!(stdout, "  {}", line(todo, false))?;
+            }
+        }
+    } else {
+        for todo in filtered {
+            writeln!(stdout, "{}", line(todo, true))?;
+        }
+    }
+    Ok(())
+}
+
+/// Format todo as a line of checkbox, due, summary and optionally context.
+fn line(todo: &Todo, with_context: bool) -> String {
+    let (checkbox, due) = match &todo.attributes {
+        Ok(agmd) => (
+            format!("[{}]", agmd.status.marker()),
+            agmd.due
+                .map(|due| due.format("%Y-%m-%d %H:%M").to_string())
+                .unwrap_or_else(|| "-".repeat(16)),
+        ),
+        Err(_) => ("[!]".to_string(), "malformed".to_string()),
+    };
+    let mut line = format!(
+        "{checkbox} {due:16} {}{}",
+        "  ".repeat(todo.depth),
+        todo.summary
+    );
+    if with_context && !todo.metadata.headings.is_empty() {
+        line.push_str(&format!("  ({})", todo.metadata.context()));
+    }
+    line
+}
@@ -0,0 +1,216 @@
+use std::{
+    collections::BTreeSet,
+    fs,
+    path::{Path, PathBuf},
+    process, thread,
+    time::Duration,
+};
+
+use agemda_core::Todo;
+use agemda_io::load::load_todos_from_root;
+use anyhow::{bail, Context};
+use chrono::{DateTime, Local, TimeDelta};
+
+use crate::{cli::NotifyCommand, config::Config};
+
+/// Alarms older than this are not fired, e.g. after a long downtime.
+const LOOKBACK: TimeDelta = TimeDelta::hours(24);
+/// Fired alarms older than this are forgotten.
+const RETENTION: TimeDelta = TimeDelta::days(7);
+
+pub fn run(command: &NotifyCommand, root: &Path, config: &Config) -> anyhow::Result<()> {
+    let mut options = config.parse_options()?;
+    let path = fired_path();
+    let mut fired = load_fired(path.as_deref())?;
+    loop {
+        // reload to pick up edits of files, and anchors of a new day
+        options.reference.today = Local::now().date_naive();
+        match load_todos_from_root(root, &options) {
+            Ok(todos) => {
+                let now = Local::now();
+                let mut is_changed = false;
+                for (todo, time) in due_alarms(&todos, now) {
+                    let key = format!("{} {}", time.to_rfc3339(), todo_key(todo));
+                    if fired.contains(&key) {
+                        continue;
+                    }
+                    if let Err(err) = fire(config, todo, time) {
+                        eprintln!("fail to notify {}: {err:#}", todo.summary);
+                    }
+                    fired.insert(key);
+                    is_changed = true;
+                }
+                if is_changed {
+                    save_fired(path.as_deref(), &mut fired, now)?;
+                }
+            }
+            Err(err) => eprintln!("fail to load todos: {err:#}"),
+        }
+        if command.once {
+            return Ok(());
+        }
+        thread::sleep(Duration::from_secs(command.interval));
+    }
+}
+
+/// Alarms of open todos due by now, within the lookback.
+fn due_alarms(todos: &[Todo], now: DateTime<Local>) -> Vec<(&Todo, DateTime<Local>)> {
+    let mut alarms = vec![];
+    for todo in todos {
+        let Ok(agmd) = &todo.attributes else {
+            continue;
+        };
+        if agmd.is_closed() {
+            continue;
+        }
+        for time in agmd.alarms.iter().filter_map(|alarm| alarm.time) {
+            if now - LOOKBACK < time && time <= now {
+                alarms.push((todo, time));
+            }
+        }
+    }
+    alarms
+}
+
+/// Run the notify command of config for the alarm.
+fn fire(config: &Config, todo: &Todo, time: DateTime<Local>) -> anyhow::Result<()> {
+    let default = ["notify-send", "agmd", "{summary}"]
+        .map(String::from)
+        .to_vec();
+    let command = match config.notify_command.is_empty() {
+        true => &default,
+        false => &config.notify_command,
+    };
+    let due = todo
+        .attributes
+        .as_ref()
+        .ok()
+        .and_then(|agmd| agmd.due)
+        .map(|due| due.format("%Y-%m-%d %H:%M").to_string())
+        .unwrap_or_default();
+    let args: Vec<_> = command
+        .iter()
+        .map(|arg| {
+            arg.replace("{summary}", &todo.summary.plain())
+                .replace("{time}", &time.format("%Y-%m-%d %H:%M").to_string())
+                .replace("{due}", &due)
+                .replace("{path}", &todo.metadata.path.to_string_lossy())
+                .replace("{uid}", todo.uid.as_deref().unwrap_or_default())
+        })
+        .collect();
+    let (program, args) = args.split_first().context("empty notify-command")?;
+    let status = process::Command::new(program)
+        .args(args)
+        .status()
+        .with_context(|| format!("fail to run {program}"))?;
+    if !status.success() {
+        bail!("{program} exited with {status}");
+    }
+    Ok(())
+}
+
+/// The key of todo in fired alarms, the uid if any, otherwise the path and summary.
+fn todo_key(todo: &Todo) -> String {
+    match &todo.uid {
+        Some(uid) => uid.clone(),
+        None => format!("{}:{}", todo.metadata.path.display(), todo.summary.plain()),
+    }
+}
+
+/// The file of fired alarms, a line of time and todo key for each.
+fn fired_path() -> Option<PathBuf> {
+    dirs::state_dir()
+        .or_else(dirs::data_local_dir)
+        .map(|dir| dir.join("agemda").join("fired"))
+}
+
+fn load_fired(path: Option<&Path>) -> anyhow::Result<BTreeSet<String>> {
+    let Some(path) = path.filter(|path| path.exists()) else {
+        return Ok(BTreeSet::new());
+    };
+    let text =
+        fs::read_to_string(path).with_context(|| format!("fail to read {}", path.display()))?;
+    Ok(text.lines().map(str::to_string).collect())
+}
+
+/// Save fired alarms, forgetting those older than the retention.
+fn save_fired(
+    path: Option<&Path>,
+    fired: &mut BTreeSet<String>,
+    now: DateTime<Local>,
+) -> anyhow::Result<()> {
+    fired.retain(|key| {
+        let time = key.split_once(' ').map_or(key.as_str(), |(time, _)| time);
+        DateTime::parse_from_rfc3339(time).is_ok_and(|time| now - RETENTION < time)
+    });
+    let Some(path) = path else {
+        return Ok(());
+    };
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir).with_context(|| format!("fail to create {}", dir.display()))?;
+    }
+    let text: String = fired.iter().map(|key| format!("{key}\n")).collect();
+    fs::write(path, text).with_context(|| format!("fail to write {}", path.display()))
+}
+
+#[cfg(test)]
+mod test {
+    use std::env;
+
+    use agemda_io::{
+        convert::of_second,
+        parse::{parse_text, ParseOptions},
+    };
+
+    use super::*;
+
+    fn todos(text: &str) -> Vec<Todo> {
+        let mut todos = vec![];
+        parse_text(&mut todos, "test.md", text, &ParseOptions::default());
+        todos
+    }
+
+    #[test]
+    fn due_alarms_within_lookback() {
+        let todos = todos(
+            "- [ ] a <agmd:2025-03-09;alarm=2025-03-08T10,2025-03-08T10:01,2025-03-09T10,2025-03-09T10:01>\n\
+             - [x] b <agmd:2025-03-09;alarm=2025-03-09T09>\n",
+        );
+        let now = of_second(2025, 3, 9, 10, 0, 0).unwrap();
+        let times: Vec<_> = due_alarms(&todos, now)
+            .into_iter()
+            .map(|(todo, time)| (todo.summary.plain(), time))
+            .collect();
+        // the lookback excludes its start, and closed todos are skipped
+        assert_eq!(
+            times,
+            [
+                ("a".to_string(), of_second(2025, 3, 8, 10, 1, 0).unwrap()),
+                ("a".to_string(), now),
+            ]
+        );
+    }
+
+    #[test]
+    fn fired_round_trip() {
+        let dir = env::temp_dir().join(format!("agemda-notify-{}", process::id()));
+        let path = dir.join("agemda").join("fired");
+        assert!(load_fired(Some(&path)).unwrap().is_empty());
+
+        let now = of_second(2025, 3, 9, 10, 0, 0).unwrap();
+        let recent = format!("{} publish", (now - TimeDelta::days(1)).to_rfc3339());
+        let old = format!("{} notes.md:a", (now - RETENTION).to_rfc3339());
+        let mut fired = BTreeSet::from([recent.clone(), old, "malformed".to_string()]);
+        save_fired(Some(&path), &mut fired, now).unwrap();
+        // pruned in place, and saved as is
+        assert_eq!(fired, BTreeSet::from([recent]));
+        assert_eq!(load_fired(Some(&path)).unwrap(), fired);
+        fs::remove_dir_all(dir).unwrap();
+
+        // without a state file, pruned only
+        let mut fired = BTreeSet::from(["malformed".to_string()]);
+        save_fired(None, &mut fired, now).unwrap();
+        assert!(fired.is_empty());
+        assert!(load_fired(None).unwrap().is_empty());
+    }
+}
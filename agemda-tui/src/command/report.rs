@@ -0,0 +1,103 @@
+use std::{
+    collections::BTreeMap,
+    io::{self, Write},
+};
+
+use agemda_core::{Filter, Todo};
+use chrono::{DateTime, Local, TimeDelta};
+
+use super::me;
+use crate::{
+    cli::{ReportBy, ReportCommand},
+    config::Config,
+};
+
+/// Estimated and spent time of a group.
+#[derive(Clone, Copy, Debug, Default)]
+struct Row {
+    estimate: TimeDelta,
+    spent: TimeDelta,
+}
+
+pub fn run(command: &ReportCommand, todos: &[Todo], config: &Config) -> anyhow::Result<()> {
+    let filter = Filter {
+        assignee: command.assignee.clone(),
+        me: me(&command.assignee, command.everyone, config),
+        should_show_completed: true,
+        ..Filter::default()
+    };
+    let now = Local::now();
+
+    let mut rows: BTreeMap<String, Row> = BTreeMap::new();
+    for todo in todos.iter().filter(|todo| filter.matches(todo)) {
+        let Ok(agmd) = &todo.attributes else {
+            continue;
+        };
+        let estimate = agmd.estimate.unwrap_or_default();
+        match command.by {
+            // estimate on due, spent on each day clocked
+            ReportBy::Day => {
+                // a due of date is the exclusive midnight after it
+                let due = agmd.due.map(|due| due - TimeDelta::nanoseconds(1));
+                if let Some(date) = due.or(agmd.start) {
+                    rows.entry(day(date)).or_default().estimate += estimate;
+                    if let Some(spent) = agmd.spent {
+                        rows.entry(day(date)).or_default().spent += spent;
+                    }
+                }
+                for clock in &todo.clocks {
+                    rows.entry(day(clock.start)).or_default().spent += clock.duration(now);
+                }
+            }
+            ReportBy::Tag => {
+                for tag in &todo.tags {
+                    let row = rows.entry(format!("#{}", tag.to_lowercase())).or_default();
+                    row.estimate += estimate;
+                    row.spent += todo.time_spent(now);
+                }
+            }
+            ReportBy::File => {
+                let path = todo.metadata.path.display().to_string();
+                let row = rows.entry(path).or_default();
+                row.estimate += estimate;
+                row.spent += todo.time_spent(now);
+            }
+        }
+    }
+
+    let mut stdout = io::stdout().lock();
+    let mut total = Row::default();
+    writeln!(stdout, "{:>8} {:>8}  group", "estimate", "spent")?;
+    for (group, row) in rows {
+        if row.estimate.is_zero() && row.spent.is_zero() {
+            continue;
+        }
+        writeln!(
+            stdout,
+            "{:>8} {:>8}  {group}",
+            format_hours(row.estimate),
+            format_hours(row.spent)
+        )?;
+        total.estimate += row.estimate;
+        total.spent += row.spent;
+    }
+    // todos of many tags are counted in each
+    if command.by != ReportBy::Tag {
+        writeln!(
+            stdout,
+            "{:>8} {:>8}  total",
+            format_hours(total.estimate),
+            format_hours(total.spent)
+        )?;
+    }
+    Ok(())
+}
+
+fn day(date_time: DateTime<Local>) -> String {
+    date_time.format("%Y-%m-%d").to_string()
+}
+
+/// Format as hours of one decimal, e.g. `1.5h`.
+pub fn format_hours(delta: TimeDelta) -> String {
+    format!("{:.1}h", delta.num_minutes() as f64 / 60.0)
+}
@@ -0,0 +1,72 @@
+use std::{fs, path::PathBuf};
+
+use agemda_io::{parse::ParseOptions, pattern::PathPattern};
+use anyhow::{anyhow, Context};
+use serde::Deserialize;
+
+/// User configuration, read from `agemda/config.toml` under the config dir.
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(default, rename_all = "kebab-case")]
+pub struct Config {
+    /// The front matter key holding the agmd link of the whole file.
+    pub front_matter_key: Option<String>,
+    /// Patterns of file path to infer the base date, e.g. `{year}-{month}-{day}.md`.
+    pub path_patterns: Vec<String>,
+    /// The name of user in `@name` and `assignee=`, to show own todos by default.
+    pub me: Option<String>,
+    /// The command run for alarms, with `{summary}`, `{time}`, `{due}`, `{path}` and `{uid}`
+    /// replaced, defaults to `["notify-send", "agmd", "{summary}"]`.
+    pub notify_command: Vec<String>,
+    /// Prefix todos in calendar with the innermost heading.
+    pub show_context: bool,
+    /// The first day of week for `eow` and `next-` weekdays, e.g. `sunday`, defaults to monday.
+    pub week_start: Option<String>,
+    /// Accept locale dates like `09/03/2025`, either `day-first` or `month-first`.
+    pub date_order: Option<String>,
+}
+
+impl Config {
+    /// Load config from given path, or the default path if not given.
+    ///
+    /// A missing default config file is not an error.
+    pub fn load(path: Option<PathBuf>) -> anyhow::Result<Self> {
+        let (path, is_default) = match path {
+            Some(path) => (path, false),
+            None => match default_path() {
+                Some(path) => (path, true),
+                None => return Ok(Self::default()),
+            },
+        };
+        if is_default && !path.exists() {
+            return Ok(Self::default());
+        }
+        let text = fs::read_to_string(&path)
+            .with_context(|| format!("fail to read config {}", path.display()))?;
+        toml::from_str(&text).with_context(|| format!("fail to parse config {}", path.display()))
+    }
+
+    pub fn parse_options(&self) -> anyhow::Result<ParseOptions> {
+        let mut options = ParseOptions::default();
+        if let Some(key) = &self.front_matter_key {
+            options.front_matter_key = key.clone();
+        }
+        options.path_patterns = self
+            .path_patterns
+            .iter()
+            .map(|pattern| PathPattern::new(pattern))
+            .collect::<anyhow::Result<_>>()?;
+        if let Some(week_start) = &self.week_start {
+            options.reference.week_start = week_start
+                .parse()
+                .map_err(|_| anyhow!("unknown week start {week_start}"))?;
+        }
+        if let Some(date_order) = &self.date_order {
+            options.date_order = Some(date_order.parse().map_err(|err: String| anyhow!(err))?);
+        }
+        Ok(options)
+    }
+}
+
+fn default_path() -> Option<PathBuf> {
+    dirs::config_dir().map(|dir| dir.join("agemda").join("config.toml"))
+}
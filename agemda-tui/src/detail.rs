@@ -0,0 +1,123 @@
+use agemda_core::Todo;
+use chrono::{Local, TimeDelta};
+use ratatui::{
+    buffer::Buffer,
+    layout::{Constraint, Flex, Layout, Rect},
+    style::Stylize,
+    text::{Line, Span, Text},
+    widgets::{Block, Clear, Paragraph, Widget, Wrap},
+};
+use ratatui_lincal::utils::summary_spans;
+
+use crate::command::report::format_hours;
+
+/// A popup showing the details of a todo.
+pub struct Detail<'a> {
+    todo: &'a Todo,
+    /// All loaded todos, for the blockers of todo.
+    todos: &'a [Todo],
+}
+
+impl<'a> Detail<'a> {
+    pub fn new(todo: &'a Todo, todos: &'a [Todo]) -> Self {
+        Self { todo, todos }
+    }
+
+    fn text(&self) -> Text<'a> {
+        let todo = self.todo;
+        let mut lines = vec![Line::from(summary_spans(&todo.summary)).bold()];
+        lines.push(Line::from(todo.metadata.path.display().to_string()).dark_gray());
+        if !todo.metadata.headings.is_empty() {
+            lines.push(Line::from(todo.metadata.context()).dark_gray());
+        }
+        if !todo.tags.is_empty() {
+            let tags: Vec<_> = todo.tags.iter().map(|tag| format!("#{tag}")).collect();
+            lines.push(Line::from(tags.join(" ")).cyan());
+        }
+        if !todo.assignees.is_empty() {
+            let assignees: Vec<_> = todo
+                .assignees
+                .iter()
+                .map(|name| format!("@{name}"))
+                .collect();
+            lines.push(Line::from(assignees.join(" ")).green());
+        }
+        lines.push(Line::default());
+        match &todo.attributes {
+            Ok(attributes) => {
+                lines.push(Line::from(format!("{:<10}{}", "status", attributes.status)));
+                if let Some(priority) = attributes.priority {
+                    lines.push(Line::from(format!("{:<10}{priority}", "priority")));
+                }
+                let dates = [
+                    ("start", attributes.start),
+                    ("due", attributes.due),
+                    ("completed", attributes.completed),
+                ];
+                let alarms = attributes.alarms.iter().map(|alarm| ("alarm", alarm.time));
+                for (name, date_time) in dates.into_iter().chain(alarms) {
+                    if let Some(date_time) = date_time {
+                        lines.push(Line::from(format!(
+                            "{name:<10}{}",
+                            date_time.format("%Y-%m-%d %H:%M")
+                        )));
+                    }
+                }
+                for (key, value) in &attributes.extensions {
+                    lines.push(Line::from(format!("{key:<10}{value}")));
+                }
+            }
+            Err(agmd) => lines.push(Line::from(format!("malformed <agmd:{agmd}>")).red()),
+        }
+        let spent = todo.time_spent(Local::now());
+        let estimate = todo.attributes.as_ref().ok().and_then(|agmd| agmd.estimate);
+        if estimate.is_some() || spent > TimeDelta::zero() {
+            let estimate = estimate.map_or("-".to_string(), format_hours);
+            let running = if todo.running_clock().is_some() {
+                " (clocking)"
+            } else {
+                ""
+            };
+            lines.push(Line::from(format!(
+                "{:<10}{} of {estimate}{running}",
+                "spent",
+                format_hours(spent)
+            )));
+        }
+        let blockers = todo.blockers(self.todos);
+        if !blockers.is_empty() {
+            lines.push(Line::default());
+            lines.push(Line::from("blocked by").yellow());
+            for blocker in blockers {
+                let blocker = &self.todos[blocker];
+                let mut spans = vec![Span::raw("  ")];
+                spans.extend(summary_spans(&blocker.summary));
+                if let Some(uid) = &blocker.uid {
+                    spans.push(Span::raw(format!(" ({uid})")).dark_gray());
+                }
+                lines.push(Line::from(spans));
+            }
+        }
+        if let Some(description) = &todo.description {
+            lines.push(Line::default());
+            lines.extend(description.markdown.lines().map(Line::from));
+        }
+        Text::from(lines)
+    }
+}
+
+impl Widget for Detail<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let [area] = Layout::horizontal([Constraint::Percentage(60)])
+            .flex(Flex::Center)
+            .areas(area);
+        let [area] = Layout::vertical([Constraint::Percentage(60)])
+            .flex(Flex::Center)
+            .areas(area);
+        Clear.render(area, buf);
+        Paragraph::new(self.text())
+            .wrap(Wrap { trim: false })
+            .block(Block::bordered().title(" Detail "))
+            .render(area, buf);
+    }
+}
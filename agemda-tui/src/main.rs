@@ -1,21 +1,34 @@
-use std::sync::Arc;
+use std::{collections::BTreeSet, fs, ops::Bound, path::PathBuf, sync::Arc};
 
-use agemda::cli::Cli;
-use agemda_core::Todo;
-use agemda_io::load::load_todos_from_root;
+use agemda::{cli::Cli, command, config::Config, detail::Detail};
+use agemda_core::{Filter, Todo};
+use agemda_io::{
+    edit::{apply_to_files, start_clock, stop_clock},
+    load::load_todos_from_root,
+};
 use chrono::{Days, Local, NaiveDate};
 use ratatui::{
     crossterm::event::{self, Event, KeyCode},
     prelude::{Buffer, Rect},
+    style::{Style, Stylize},
     widgets::{StatefulWidget, Widget},
     DefaultTerminal,
 };
-use ratatui_lincal::{calendar::Calendar, data::CalendarState, utils::has_overlap};
+use ratatui_lincal::{calendar::Calendar, data::CalendarState, utils::todos_of_date};
+use unicode_width::UnicodeWidthStr;
 
 fn main() -> Result<(), Box<dyn std::error::Error>> {
     let cli: Cli = argh::from_env();
+    let config = Config::load(cli.config.clone())?;
+
+    if let Some(command) = &cli.command {
+        let todos = load_todos_from_root(&cli.root, &config.parse_options()?)?;
+        command::run(command, &cli.root, &todos, &config)?;
+        return Ok(());
+    }
+
     let mut terminal = ratatui::init();
-    App::new(cli)?.run(&mut terminal)?;
+    App::new(cli, config)?.run(&mut terminal)?;
     ratatui::restore();
     Ok(())
 }
@@ -24,8 +37,13 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
 
 struct App {
     cli: Cli,
+    config: Config,
     should_quit: bool,
-    should_show_completed: bool,
+    should_show_context: bool,
+    should_show_detail: bool,
+
+    /// Filter of todos shown in calendar.
+    filter: Filter,
 
     day_width: u16,
 
@@ -41,10 +59,16 @@ struct App {
 }
 
 impl App {
-    /// Create a new app using given cli options.
-    pub fn new(cli: Cli) -> Result<Self, Box<dyn std::error::Error>> {
+    /// Create a new app using given cli options and config.
+    pub fn new(cli: Cli, config: Config) -> Result<Self, Box<dyn std::error::Error>> {
         let should_quit = false;
-        let should_show_completed = false;
+        let should_show_context = config.show_context;
+        let should_show_detail = false;
+        // own todos by default
+        let filter = Filter {
+            me: config.me.clone(),
+            ..Filter::default()
+        };
 
         // TODO: make into cli option and dynamically changable
         let day_width = 25;
@@ -54,12 +78,15 @@ impl App {
 
         let state = CalendarState::new(today);
 
-        let data = Arc::new(load_todos_from_root(&cli.root)?);
+        let data = Arc::new(load_todos_from_root(&cli.root, &config.parse_options()?)?);
 
         Ok(Self {
             cli,
+            config,
             should_quit,
-            should_show_completed,
+            should_show_context,
+            should_show_detail,
+            filter,
             day_width,
             today,
             start,
@@ -91,6 +118,7 @@ impl App {
     /// How the app handle events.
     ///
     /// Currently the keybinding is hardcoded and handle only key event.
+    #[allow(clippy::single_match)]
     pub fn handle_event(&mut self, event: Event) -> Result<(), Box<dyn std::error::Error>> {
         match event {
             // handle key only
@@ -101,8 +129,18 @@ impl App {
                     KeyCode::Char('q') => self.should_quit = true,
                     KeyCode::Char('r') => self.reload()?,
                     KeyCode::Char('.') => self.toggle_show_completed(),
+                    KeyCode::Char('c') => self.toggle_show_context(),
+                    KeyCode::Char('i') => self.toggle_show_detail(),
+                    KeyCode::Char('t') => self.select_next_tag(),
+                    KeyCode::Char('T') => self.filter.tag = None,
+                    KeyCode::Char('a') => self.select_next_assignee(),
+                    KeyCode::Char('A') => self.toggle_show_everyone(),
+                    KeyCode::Esc => self.should_show_detail = false,
                     // TODO: d for show overdue
                     KeyCode::Enter => self.open_selected(),
+                    KeyCode::Char('s') => self.toggle_clock_selected()?,
+                    KeyCode::Char('z') => self.toggle_collapse_selected(),
+                    KeyCode::Char('Z') => self.toggle_collapse_all(),
                     KeyCode::Char('k') | KeyCode::Up => self.state.select_previous_item(),
                     KeyCode::Char('j') | KeyCode::Down => self.state.select_next_item(),
                     KeyCode::Char('h') | KeyCode::Left => self.state.select_previous(),
@@ -119,28 +157,151 @@ impl App {
         Ok(())
     }
 
-    /// Reload data
+    /// Reload data, keeping the selected and collapsed todos.
     pub fn reload(&mut self) -> Result<(), Box<dyn std::error::Error>> {
-        self.data = Arc::new(load_todos_from_root(&self.cli.root)?);
+        // indices are changed by reload, so find the todos by key
+        let selected = self.selected().map(|(_, todo)| TodoKey::of(todo));
+        let collapsed: Vec<_> = self
+            .state
+            .collapsed
+            .iter()
+            .map(|&index| TodoKey::of(&self.data[index]))
+            .collect();
+
+        self.data = Arc::new(load_todos_from_root(
+            &self.cli.root,
+            &self.config.parse_options()?,
+        )?);
         self.today = Local::now().date_naive();
+
+        self.state.collapsed = self
+            .data
+            .iter()
+            .enumerate()
+            .filter(|(_, todo)| collapsed.contains(&TodoKey::of(todo)))
+            .map(|(index, _)| index)
+            .collect();
+        if let Some(key) = selected {
+            self.select_todo(&key);
+        }
         Ok(())
     }
 
+    /// Select the todo of key at its due date, if shown.
+    fn select_todo(&mut self, key: &TodoKey) {
+        let Some(due) = self
+            .data
+            .iter()
+            .find(|todo| TodoKey::of(todo) == *key)
+            .and_then(|todo| todo.attributes.as_ref().ok()?.due)
+        else {
+            return;
+        };
+        let date = due.date_naive();
+        let position = todos_of_date(&self.data, date, &self.filter, &self.state.collapsed)
+            .iter()
+            .position(|(_, todo)| TodoKey::of(todo) == *key);
+        if let Some(position) = position {
+            self.state.selected = date;
+            self.state.selected_item = position;
+        }
+    }
+
     pub fn toggle_show_completed(&mut self) {
-        self.should_show_completed = !self.should_show_completed;
+        self.filter.should_show_completed = !self.filter.should_show_completed;
+    }
+
+    /// Filter by the next tag in alphabetical order, or no tag after the last.
+    pub fn select_next_tag(&mut self) {
+        let tags = self.data.iter().flat_map(|todo| &todo.tags);
+        self.filter.tag = next_name(tags, self.filter.tag.as_ref());
+        self.state.selected_item = 0;
+    }
+
+    /// Filter by the next assignee in alphabetical order, or everyone after the last.
+    pub fn select_next_assignee(&mut self) {
+        let assignees = self.data.iter().flat_map(|todo| &todo.assignees);
+        self.filter.assignee = next_name(assignees, self.filter.assignee.as_ref());
+        self.filter.me = None;
+        self.state.selected_item = 0;
+    }
+
+    /// Show todos of everyone, or only own todos if `me` is configured.
+    pub fn toggle_show_everyone(&mut self) {
+        self.filter.me = match (&self.filter.me, &self.filter.assignee) {
+            (None, None) => self.config.me.clone(),
+            _ => None,
+        };
+        self.filter.assignee = None;
+        self.state.selected_item = 0;
+    }
+
+    pub fn toggle_show_context(&mut self) {
+        self.should_show_context = !self.should_show_context;
+    }
+
+    pub fn toggle_show_detail(&mut self) {
+        self.should_show_detail = !self.should_show_detail;
+    }
+
+    /// The selected todo with its index in data.
+    pub fn selected(&self) -> Option<(usize, &Todo)> {
+        todos_of_date(
+            &self.data,
+            self.state.selected,
+            &self.filter,
+            &self.state.collapsed,
+        )
+        .into_iter()
+        .nth(self.state.selected_item)
     }
 
     pub fn open_selected(&self) {
-        let mut filtered = self
-            .data
-            .iter()
-            .filter(|todo| has_overlap(todo, self.state.selected, self.should_show_completed));
-        if let Some(selected) = filtered.nth(self.state.selected_item) {
+        if let Some((_, selected)) = self.selected() {
             let path = selected.metadata.path.as_path();
             _ = open::that_detached(path);
         }
     }
 
+    /// Start a clock on the selected todo, or stop its running clock.
+    pub fn toggle_clock_selected(&mut self) -> Result<(), Box<dyn std::error::Error>> {
+        let Some((_, selected)) = self.selected() else {
+            return Ok(());
+        };
+        let now = Local::now();
+        let text = fs::read_to_string(&selected.metadata.path)?;
+        let edit = match selected.running_clock() {
+            Some(clock) => stop_clock(&text, selected, clock, now),
+            None => start_clock(&text, selected, now),
+        };
+        apply_to_files(&[edit])?;
+        self.reload()
+    }
+
+    /// Collapse or expand children of the selected todo.
+    pub fn toggle_collapse_selected(&mut self) {
+        if let Some((index, selected)) = self.selected() {
+            if !selected.children.is_empty() {
+                self.state.toggle_collapsed(index);
+            }
+        }
+    }
+
+    /// Expand all if any todo is collapsed, otherwise collapse all.
+    pub fn toggle_collapse_all(&mut self) {
+        if self.state.collapsed.is_empty() {
+            self.state.collapsed = self
+                .data
+                .iter()
+                .enumerate()
+                .filter(|(_, todo)| !todo.children.is_empty())
+                .map(|(index, _)| index)
+                .collect();
+        } else {
+            self.state.collapsed.clear();
+        }
+    }
+
     pub fn select_previous_start(&mut self) {
         self.start = self.start.checked_sub_days(Days::new(1)).unwrap();
     }
@@ -160,8 +321,60 @@ impl Widget for &mut App {
             self.today,
             self.start,
             self.day_width,
-            self.should_show_completed,
+            self.filter.clone(),
+            self.should_show_context,
         );
         StatefulWidget::render(calendar, area, buf, &mut self.state);
+        // label of active filters
+        let mut label = String::new();
+        if let Some(assignee) = &self.filter.assignee {
+            label.push_str(&format!(" @{assignee} "));
+        }
+        if let Some(me) = &self.filter.me {
+            label.push_str(&format!(" @{me} or unassigned "));
+        }
+        if let Some(tag) = &self.filter.tag {
+            label.push_str(&format!(" #{tag} "));
+        }
+        let x = area.right().saturating_sub(label.width() as u16);
+        buf.set_string(x, area.y, label, Style::default().reversed());
+        if self.should_show_detail {
+            if let Some((_, selected)) = self.selected() {
+                Detail::new(selected, &self.data).render(area, buf);
+            }
+        }
+    }
+}
+
+/// The name after `current` in alphabetical order, case insensitive,
+/// the first if `current` is `None`, or `None` after the last.
+fn next_name<'a>(
+    names: impl Iterator<Item = &'a String>,
+    current: Option<&String>,
+) -> Option<String> {
+    let names: BTreeSet<_> = names.map(|name| name.to_lowercase()).collect();
+    match current {
+        Some(current) => names
+            .range::<String, _>((Bound::Excluded(&current.to_lowercase()), Bound::Unbounded))
+            .next()
+            .cloned(),
+        None => names.first().cloned(),
+    }
+}
+
+/// The key to find the same todo after reload,
+/// the uid if any, otherwise the path and summary.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum TodoKey {
+    Uid(String),
+    PathSummary(PathBuf, String),
+}
+
+impl TodoKey {
+    fn of(todo: &Todo) -> Self {
+        match &todo.uid {
+            Some(uid) => TodoKey::Uid(uid.clone()),
+            None => TodoKey::PathSummary(todo.metadata.path.clone(), todo.summary.plain()),
+        }
     }
 }
@@ -1,10 +1,11 @@
+use agemda_core::Filter;
 use chrono::{Days, NaiveDate};
 use ratatui::{buffer::Buffer, layout::Rect, widgets::StatefulWidget};
 
 use crate::{
     data::{CalendarData, CalendarState},
     row::CalendarRow,
-    utils::has_overlap,
+    utils::todos_of_date,
 };
 
 /// Calendar widget.
@@ -13,7 +14,8 @@ pub struct Calendar {
     start: NaiveDate,
     today: NaiveDate,
     day_width: u16,
-    should_show_completed: bool,
+    filter: Filter,
+    should_show_context: bool,
 }
 
 impl Calendar {
@@ -22,14 +24,16 @@ impl Calendar {
         today: NaiveDate,
         start: NaiveDate,
         day_width: u16,
-        should_show_completed: bool,
+        filter: Filter,
+        should_show_context: bool,
     ) -> Self {
         Self {
             data,
             today,
             start,
             day_width,
-            should_show_completed,
+            filter,
+            should_show_context,
         }
     }
 }
@@ -61,8 +65,7 @@ impl StatefulWidget for Calendar {
                     .checked_add_days(Days::new(day_index as u64))
                     .unwrap();
                 // TODO: duplicate filtering shoud be cached and passed
-                let filtered = self.data.iter().filter(|todo| has_overlap(todo, date, self.should_show_completed));
-                filtered.count()
+                todos_of_date(&self.data, date, &self.filter, &state.collapsed).len()
             })
             .max()
             .unwrap_or(0)
@@ -80,7 +83,8 @@ impl StatefulWidget for Calendar {
                 self.today,
                 row_start,
                 self.day_width,
-                self.should_show_completed,
+                self.filter.clone(),
+                self.should_show_context,
             )
             .render(row_area, buf, state);
 
@@ -1,4 +1,4 @@
-use std::sync::Arc;
+use std::{collections::HashSet, sync::Arc};
 
 use agemda_core::Todo;
 use chrono::{Days, NaiveDate};
@@ -9,6 +9,8 @@ pub type CalendarData = Arc<Vec<Todo>>;
 pub struct CalendarState {
     pub selected: NaiveDate,
     pub selected_item: usize,
+    /// Indices of todos whose children are hidden.
+    pub collapsed: HashSet<usize>,
 }
 
 impl CalendarState {
@@ -16,6 +18,7 @@ impl CalendarState {
         Self {
             selected,
             selected_item: 0,
+            collapsed: HashSet::new(),
         }
     }
 
@@ -36,4 +39,11 @@ impl CalendarState {
     pub fn select_previous_item(&mut self) {
         self.selected_item = self.selected_item.saturating_sub(1);
     }
+
+    /// Collapse the todo if expanded, otherwise expand it.
+    pub fn toggle_collapsed(&mut self, index: usize) {
+        if !self.collapsed.remove(&index) {
+            self.collapsed.insert(index);
+        }
+    }
 }
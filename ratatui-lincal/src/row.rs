@@ -1,14 +1,16 @@
+use agemda_core::Filter;
 use chrono::{Datelike, Days, NaiveDate};
 use ratatui::{
     buffer::Buffer,
     layout::Rect,
     style::{Style, Stylize},
+    text::{Line, Span},
     widgets::StatefulWidget,
 };
 
 use crate::{
     data::{CalendarData, CalendarState},
-    utils::{has_overlap, set_string_opt},
+    utils::{set_line_opt, set_string_opt, status_style, summary_spans, todos_of_date},
 };
 
 /// Calendar row widget.
@@ -17,7 +19,8 @@ pub struct CalendarRow {
     start: NaiveDate,
     today: NaiveDate,
     day_width: u16,
-    should_show_completed: bool,
+    filter: Filter,
+    should_show_context: bool,
 }
 
 impl CalendarRow {
@@ -26,14 +29,16 @@ impl CalendarRow {
         today: NaiveDate,
         start: NaiveDate,
         day_width: u16,
-        should_show_completed: bool,
+        filter: Filter,
+        should_show_context: bool,
     ) -> Self {
         Self {
             data,
             today,
             start,
             day_width,
-            should_show_completed,
+            filter,
+            should_show_context,
         }
     }
 }
@@ -72,11 +77,7 @@ impl StatefulWidget for CalendarRow {
             let is_selected_date = state.selected == date;
 
             // filter out data of this date
-            let filtered: Vec<_> = self
-                .data
-                .iter()
-                .filter(|todo| has_overlap(todo, date, self.should_show_completed))
-                .collect();
+            let filtered = todos_of_date(&self.data, date, &self.filter, &state.collapsed);
 
             // TODO: fix selection out of range
             // if state.selected_item >= filtered.len() {
@@ -111,28 +112,53 @@ impl StatefulWidget for CalendarRow {
             );
 
             // render each todo item
-            for (item_index, item) in filtered.iter().enumerate() {
+            for (item_index, (data_index, item)) in filtered.iter().enumerate() {
                 // the first y is kept empty for visual separation, so plus 1
                 let y = area.y + 1 + item_index as u16;
 
                 let is_selected_item = is_selected_date && state.selected_item == item_index;
 
                 // TODO: padding and trim
-                let style = if let Ok(agmd) = &item.attributes {
-                    if agmd.completed.is_some() {
-                        default_style.dim()
-                    } else {
-                        default_style
+                let style = match &item.attributes {
+                    // blocked todo is not actionable yet, whatever its priority
+                    Ok(agmd) if !agmd.is_closed() && item.is_blocked(&self.data) => {
+                        status_style(agmd.status).dim()
                     }
-                } else {
-                    default_style
+                    Ok(agmd) if agmd.is_high_priority() && !agmd.is_closed() => {
+                        status_style(agmd.status).light_red().bold()
+                    }
+                    Ok(agmd) => status_style(agmd.status),
+                    Err(_) => default_style,
                 };
                 let style = if is_selected_item {
                     style.reversed()
                 } else {
                     style
                 };
-                set_string_opt(buf, x + 2, y, &item.summary, style);
+                // indent children, prefix context and roll up progress of parent
+                let mut prefix = " ".repeat(item.depth * 2);
+                if self.should_show_context
+                    && let Some(heading) = item.metadata.headings.last()
+                {
+                    prefix.push_str(&format!("[{heading}] "));
+                }
+                let mut spans = vec![Span::raw(prefix)];
+                spans.extend(summary_spans(&item.summary));
+                if let Some(progress) = item.progress(&self.data) {
+                    let folded = if state.collapsed.contains(data_index) {
+                        "+"
+                    } else {
+                        ""
+                    };
+                    spans.push(Span::raw(format!(
+                        " {}/{}{folded}",
+                        progress.done, progress.total
+                    )));
+                }
+                if item.running_clock().is_some() {
+                    spans.push(Span::raw(" ◷"));
+                }
+                set_line_opt(buf, x + 2, y, &Line::from(spans).style(style));
             }
 
             // TODO: render today indicator if is today
@@ -156,3 +182,39 @@ impl StatefulWidget for CalendarRow {
         set_string_opt(buf, last_tick_x, y_label, ">", default_style);
     }
 }
+
+#[cfg(test)]
+mod test {
+    use std::sync::Arc;
+
+    use agemda_core::graph::resolve_dependencies;
+    use agemda_io::parse::{ParseOptions, parse_text};
+    use ratatui::style::{Color, Modifier};
+
+    use super::*;
+
+    #[test]
+    fn blocked_high_priority_is_dimmed() {
+        let text = "- [ ] publish <agmd:2025-03-09T10;priority=1;depends=freeze>\n\
+                    - [ ] freeze <agmd:2025-03-09T10;priority=1;uid=freeze>\n";
+        let mut todos = vec![];
+        parse_text(&mut todos, "test.md", text, &ParseOptions::default());
+        resolve_dependencies(&mut todos);
+
+        let date = NaiveDate::from_ymd_opt(2025, 3, 9).unwrap();
+        let row = CalendarRow::new(Arc::new(todos), date, date, 20, Filter::default(), false);
+        let area = Rect::new(0, 0, 20, 5);
+        let mut buf = Buffer::empty(area);
+        row.render(area, &mut buf, &mut CalendarState::new(date));
+
+        // sorted by priority then path, so in order of file
+        let publish = &buf[(2, 1)];
+        assert_eq!(publish.symbol(), "p");
+        assert!(publish.modifier.contains(Modifier::DIM));
+        assert!(!publish.modifier.contains(Modifier::BOLD));
+        let freeze = &buf[(2, 2)];
+        assert_eq!(freeze.symbol(), "f");
+        assert_eq!(freeze.fg, Color::LightRed);
+        assert!(freeze.modifier.contains(Modifier::BOLD));
+    }
+}
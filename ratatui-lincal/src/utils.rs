@@ -1,6 +1,12 @@
-use agemda_core::Todo;
-use chrono::NaiveDate;
-use ratatui::{buffer::Buffer, style::Style};
+use std::{collections::HashSet, path::Path};
+
+use agemda_core::{Filter, SpanStyle, Status, Summary, Todo};
+use chrono::{DateTime, Local, NaiveDate};
+use ratatui::{
+    buffer::Buffer,
+    style::{Style, Stylize},
+    text::{Line, Span},
+};
 
 pub fn set_string_opt(
     buf: &mut Buffer,
@@ -14,8 +20,57 @@ pub fn set_string_opt(
     }
 }
 
+pub fn set_line_opt(buf: &mut Buffer, x: u16, y: u16, line: &Line) {
+    if y >= buf.area().top() && y < buf.area.bottom() {
+        let width = buf.area.right().saturating_sub(x);
+        buf.set_line(x, y, line, width);
+    }
+}
+
+/// Spans of summary styled after its inline markdown.
+pub fn summary_spans(summary: &Summary) -> Vec<Span<'_>> {
+    summary
+        .spans
+        .iter()
+        .map(|span| Span::styled(span.text.as_str(), span_style(&span.style)))
+        .collect()
+}
+
+fn span_style(style: &SpanStyle) -> Style {
+    let mut result = Style::default();
+    if style.code {
+        result = result.cyan();
+    }
+    if style.emphasis {
+        result = result.italic();
+    }
+    if style.strong {
+        result = result.bold();
+    }
+    if style.strikethrough {
+        result = result.crossed_out();
+    }
+    if style.link {
+        result = result.underlined();
+    }
+    result
+}
+
+/// The style of todo in the status.
+pub fn status_style(status: Status) -> Style {
+    let style = Style::default();
+    match status {
+        Status::NeedsAction => style,
+        Status::InProcess => style.yellow(),
+        Status::Completed => style.dim(),
+        Status::Cancelled => style.dim().crossed_out(),
+        Status::Deferred => style.dark_gray().italic(),
+        Status::Question => style.magenta(),
+    }
+}
+
 /// Utility function for filtering todo out of day.
-pub fn has_overlap(todo: &Todo, date: NaiveDate, should_show_completed: bool) -> bool {
+pub fn has_overlap(todo: &Todo, date: NaiveDate) -> bool {
     if let Ok(agmd) = &todo.attributes {
         // match (agmd.start, agmd.due) {
         //     // TODO: both none for ad-hoc for every day
@@ -31,18 +86,97 @@ pub fn has_overlap(todo: &Todo, date: NaiveDate, should_show_completed: bool) ->
         //     _ => false,
         // }
         // TODO: too many, only due currently
-        let is_due = if let Some(due) = agmd.due {
+        if let Some(due) = agmd.due {
             due.date_naive() == date
         } else {
             false
-        };
-        if should_show_completed {
-            is_due
-        } else {
-            is_due && agmd.completed.is_none()
         }
     } else {
         // TODO: handle malform
         false
     }
 }
+
+/// Todos rendered at the date, with their indices in data.
+///
+/// Todos not matching the filter or under collapsed todos are hidden.
+/// Todos are sorted by priority, then time, then path,
+/// keeping children under their parent.
+pub fn todos_of_date<'a>(
+    data: &'a [Todo],
+    date: NaiveDate,
+    filter: &Filter,
+    collapsed: &HashSet<usize>,
+) -> Vec<(usize, &'a Todo)> {
+    let mut todos: Vec<_> = data
+        .iter()
+        .enumerate()
+        .filter(|(_, todo)| has_overlap(todo, date) && filter.matches(todo))
+        .filter(|(_, todo)| !is_collapsed_under(data, todo, collapsed))
+        .collect();
+    let shown: HashSet<_> = todos.iter().map(|(index, _)| *index).collect();
+    todos.sort_by_cached_key(|(index, _)| {
+        // keys from the outermost shown ancestor to the todo itself
+        let mut keys = vec![sort_key(data, *index)];
+        let mut parent = data[*index].parent;
+        while let Some(index) = parent.filter(|index| shown.contains(index)) {
+            keys.push(sort_key(data, index));
+            parent = data[index].parent;
+        }
+        keys.reverse();
+        keys
+    });
+    todos
+}
+
+/// The key of todo to sort by priority, then time, then path.
+///
+/// Todos without priority come last, as the undefined 0 of VTODO.
+/// The time is the start, or the due without start, and todos without time come last.
+fn sort_key(data: &[Todo], index: usize) -> (u8, bool, Option<DateTime<Local>>, &Path, usize) {
+    let todo = &data[index];
+    let agmd = todo.attributes.as_ref().ok();
+    let time = agmd.and_then(|agmd| agmd.start.or(agmd.due));
+    (
+        agmd.and_then(|agmd| agmd.priority).unwrap_or(u8::MAX),
+        time.is_none(),
+        time,
+        &todo.metadata.path,
+        index,
+    )
+}
+
+/// Whether any ancestor of the todo is collapsed.
+fn is_collapsed_under(data: &[Todo], todo: &Todo, collapsed: &HashSet<usize>) -> bool {
+    let mut parent = todo.parent;
+    while let Some(index) = parent {
+        if collapsed.contains(&index) {
+            return true;
+        }
+        parent = data[index].parent;
+    }
+    false
+}
+
+#[cfg(test)]
+mod test {
+    use agemda_io::parse::{ParseOptions, parse_text};
+
+    use super::*;
+
+    #[test]
+    fn sort_by_priority_then_time() {
+        let text = "- [ ] late <agmd:2025-03-09T15>\n\
+                    - [ ] due only <agmd:due=2025-03-09T12>\n\
+                    - [ ] early start <agmd:2025-03-08T09..2025-03-09T18>\n\
+                    - [ ] urgent <agmd:2025-03-09T20;priority=1>\n";
+        let mut todos = vec![];
+        parse_text(&mut todos, "test.md", text, &ParseOptions::default());
+        let date = NaiveDate::from_ymd_opt(2025, 3, 9).unwrap();
+        let sorted: Vec<_> = todos_of_date(&todos, date, &Filter::default(), &HashSet::new())
+            .into_iter()
+            .map(|(_, todo)| todo.summary.plain())
+            .collect();
+        assert_eq!(sorted, ["urgent", "early start", "due only", "late"]);
+    }
+}